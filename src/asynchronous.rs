@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+use std::panic;
+use std::sync::Arc;
+
+use tera::Context;
+
+use crate::{LanguagePreference, Terarium, TerariumError};
+
+/// Async facade over the shared `Terarium` instance for tokio-based services.
+///
+/// Renders are offloaded to the tokio blocking thread pool by `tokio::task::spawn_blocking`, so large renders do not
+/// block the executor. Arguments are owned because the render outlives the caller's borrow. When used with the
+/// `ReloadableTerarium`, create the facade from the `current()` snapshot for each request.
+#[derive(Clone)]
+pub struct AsyncTerarium {
+    /// Shared instance.
+    terarium: Arc<Terarium>,
+}
+
+
+impl AsyncTerarium {
+    /// Create new facade over the shared instance.
+    pub fn new(terarium: Arc<Terarium>) -> Self {
+        Self { terarium }
+    }
+
+    /// Get the shared instance.
+    pub fn terarium(&self) -> &Arc<Terarium> {
+        &self.terarium
+    }
+
+    /// Render single template on the blocking thread pool.
+    /// See `Terarium::render_template_with_preference()` for details.
+    pub async fn render_template(
+        &self,
+        context: Context,
+        template_key: impl Into<String>,
+        preference: impl Into<LanguagePreference>,
+    ) -> Result<String, TerariumError> {
+        let template_key = template_key.into();
+        let preference = preference.into();
+        self.spawn(move |terarium| terarium.render_template_with_preference(&context, &template_key, &preference)).await
+    }
+
+    /// Render template group on the blocking thread pool.
+    /// See `Terarium::render_group_with_preference()` for details.
+    pub async fn render_group(
+        &self,
+        context: Context,
+        group_key: impl Into<String>,
+        preference: impl Into<LanguagePreference>,
+    ) -> Result<HashMap<String, String>, TerariumError> {
+        let group_key = group_key.into();
+        let preference = preference.into();
+        self.spawn(move |terarium| terarium.render_group_with_preference(&context, &group_key, &preference)).await
+    }
+
+    /// Run the `render` function on the blocking thread pool.
+    /// Panic of the render is propagated to the caller, the render cancelled by the runtime shutdown returns the
+    /// `TerariumError::RenderCancelled` error.
+    async fn spawn<T, F>(&self, render: F) -> Result<T, TerariumError>
+        where
+            T: Send + 'static,
+            F: FnOnce(&Terarium) -> Result<T, TerariumError> + Send + 'static,
+    {
+        let terarium = self.terarium.clone();
+        match tokio::task::spawn_blocking(move || render(&terarium)).await {
+            Ok(result) => result,
+            Err(err) if err.is_panic() => panic::resume_unwind(err.into_panic()),
+            Err(_) => Err(TerariumError::RenderCancelled),
+        }
+    }
+}
+
+
+impl From<Arc<Terarium>> for AsyncTerarium {
+    fn from(value: Arc<Terarium>) -> Self {
+        Self::new(value)
+    }
+}
+
+
+impl From<Terarium> for AsyncTerarium {
+    fn from(value: Terarium) -> Self {
+        Self::new(Arc::new(value))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::future::Future;
+    use std::task::{self, Poll, Wake, Waker};
+
+    use crate::{Content, Template, TemplateGroupBuilder, TerariumBuilder};
+
+    use super::*;
+
+    #[test]
+    fn render_template() {
+        let terarium = make_instance();
+        let mut ctx = Context::new();
+        ctx.insert("name", "john");
+
+        let rendered = block_on(terarium.render_template(ctx.clone(), "subject", "cs"));
+        assert_eq!(rendered.unwrap(), "Ahoj john");
+        let preference = LanguagePreference::new("de").with_fallback("en");
+        let rendered = block_on(terarium.render_template(ctx, "subject", preference));
+        assert_eq!(rendered.unwrap(), "Hello john");
+    }
+
+    #[test]
+    fn render_group() {
+        let terarium = make_instance();
+        let rendered = block_on(terarium.render_group(Context::new(), "email", "en")).unwrap();
+        assert_eq!(rendered["subject"], "Hello ");
+
+        let result = block_on(terarium.render_group(Context::new(), "missing", "en"));
+        assert!(matches!(result.unwrap_err(), TerariumError::GroupNotFound));
+    }
+
+    #[test]
+    fn cancelled_render() {
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let handle = runtime.handle().clone();
+        drop(runtime);
+
+        // Renders spawned after the runtime shutdown are cancelled immediately.
+        let _guard = handle.enter();
+        let terarium = make_instance();
+        let mut render = Box::pin(terarium.render_template(Context::new(), "subject", "en"));
+        let waker = Waker::from(Arc::new(NoopWaker));
+        let Poll::Ready(result) = render.as_mut().poll(&mut task::Context::from_waker(&waker)) else {
+            panic!("Cancelled render is pending");
+        };
+        assert!(matches!(result.unwrap_err(), TerariumError::RenderCancelled));
+    }
+
+    struct NoopWaker;
+
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(future)
+    }
+
+    fn make_instance() -> AsyncTerarium {
+        let mut builder = TerariumBuilder::default();
+        builder.add_template(
+            "subject".to_owned(),
+            Template::new(vec![
+                Content::new("Hello {{ name | default(value='') }}".to_owned(), vec!["en".to_owned()]),
+                Content::new("Ahoj {{ name }}".to_owned(), vec!["cs".to_owned()]),
+            ]).unwrap(),
+        ).unwrap();
+        builder.add_group(
+            "email".to_owned(),
+            TemplateGroupBuilder::default().add_member("subject".to_owned(), "subject".to_owned()).build(),
+        ).unwrap();
+        builder.build().unwrap().into()
+    }
+}
@@ -0,0 +1,262 @@
+/// Ordered list of preferred languages.
+/// The first language is the primary one, other languages are fallbacks tried in the order they were added.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct LanguagePreference {
+    /// Languages in order of preference.
+    languages: Vec<String>,
+}
+
+
+impl LanguagePreference {
+    /// Create new preference with the primary `language`.
+    pub fn new(language: impl Into<String>) -> Self {
+        Self { languages: vec![language.into()] }
+    }
+
+    /// Add fallback language at the end of the preference list.
+    pub fn with_fallback(mut self, language: impl Into<String>) -> Self {
+        self.languages.push(language.into());
+        self
+    }
+
+    /// Add fallback languages at the end of the preference list.
+    pub fn with_fallbacks<S: Into<String>>(mut self, languages: impl IntoIterator<Item=S>) -> Self {
+        self.languages.extend(languages.into_iter().map(|language| language.into()));
+        self
+    }
+
+    /// Get languages in order of preference.
+    pub fn languages(&self) -> &[String] {
+        &self.languages
+    }
+
+    /// Get the most preferred language.
+    pub fn primary(&self) -> Option<&str> {
+        self.languages.first().map(|language| language.as_str())
+    }
+
+    /// Create preference from the HTTP `Accept-Language` header. Languages are ordered by their quality.
+    pub fn from_accept_language(header: &str) -> Self {
+        parse_accept_language(header).into_iter().map(|(language, _)| language).collect()
+    }
+
+    /// Return `true` if there is no language in the preference list.
+    pub fn is_empty(&self) -> bool {
+        self.languages.is_empty()
+    }
+}
+
+
+impl From<&str> for LanguagePreference {
+    fn from(value: &str) -> Self {
+        Self::new(value)
+    }
+}
+
+
+impl From<String> for LanguagePreference {
+    fn from(value: String) -> Self {
+        Self::new(value)
+    }
+}
+
+
+impl<S: Into<String>> FromIterator<S> for LanguagePreference {
+    fn from_iter<T: IntoIterator<Item=S>>(iter: T) -> Self {
+        Self::default().with_fallbacks(iter)
+    }
+}
+
+
+/// Parse the HTTP `Accept-Language` header into pairs of language and its quality ordered by the quality (languages
+/// with the same quality keep their order). The `*` wildcard, malformed items and languages with zero quality are
+/// dropped.
+pub fn parse_accept_language(header: &str) -> Vec<(String, f32)> {
+    let mut languages = Vec::<(String, f32)>::new();
+    for item in header.split(',') {
+        let mut parts = item.split(';').map(|part| part.trim());
+        let language = parts.next().unwrap_or_default();
+        if language.is_empty() || language == "*" {
+            continue;
+        }
+        let mut quality = Some(1.0);
+        for parameter in parts {
+            if let Some((name, value)) = parameter.split_once('=') {
+                if name.trim().eq_ignore_ascii_case("q") {
+                    quality = value.trim().parse::<f32>().ok().filter(|q| (0.0..=1.0).contains(q));
+                }
+            }
+        }
+        match quality {
+            Some(quality) if quality > 0.0 => languages.push((language.to_owned(), quality)),
+            _ => continue,
+        }
+    }
+    languages.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+    languages
+}
+
+
+/// Normalize the BCP 47 language tag.
+///
+/// Subtags are converted to their canonical case (`EN-latn-us` is normalized to `en-Latn-US`) and the `_` separator is
+/// replaced by `-`. Return `None` if the tag is not well-formed.
+pub fn normalize_language_tag(tag: &str) -> Option<String> {
+    let subtags = tag.split(['-', '_']).collect::<Vec<_>>();
+    let valid_subtag = |subtag: &&str| {
+        !subtag.is_empty() && subtag.len() <= 8 && subtag.chars().all(|c| c.is_ascii_alphanumeric())
+    };
+    if !subtags.iter().all(valid_subtag) {
+        return None;
+    }
+
+    let language = subtags[0];
+    let private_use = language.eq_ignore_ascii_case("x");
+    let valid_language = language.len() >= 2 && language.chars().all(|c| c.is_ascii_alphabetic());
+    if !(private_use || valid_language) {
+        return None;
+    }
+
+    let mut result = Vec::<String>::with_capacity(subtags.len());
+    result.push(language.to_ascii_lowercase());
+    // Script and region subtags are recognized only before the first extension or private use singleton.
+    let mut in_extension = private_use;
+    for subtag in subtags.iter().skip(1) {
+        if subtag.len() == 1 {
+            in_extension = true;
+            result.push(subtag.to_ascii_lowercase());
+        } else if !in_extension && subtag.len() == 4 && subtag.chars().all(|c| c.is_ascii_alphabetic()) {
+            let mut script = subtag.to_ascii_lowercase();
+            script[..1].make_ascii_uppercase();
+            result.push(script);
+        } else if !in_extension && (
+            (subtag.len() == 2 && subtag.chars().all(|c| c.is_ascii_alphabetic()))
+                || (subtag.len() == 3 && subtag.chars().all(|c| c.is_ascii_digit()))
+        ) {
+            result.push(subtag.to_ascii_uppercase());
+        } else {
+            result.push(subtag.to_ascii_lowercase());
+        }
+    }
+    if in_extension && result.last().map(|subtag| subtag.len() == 1).unwrap_or(false) {
+        return None;
+    }
+    Some(result.join("-"))
+}
+
+
+/// Get chain of language tags from the most specific to the least specific one (`de-Latn-AT`, `de-Latn`, `de`).
+///
+/// The tag is normalized first and then subtags are removed from the end. Singletons left at the end of the tag are
+/// removed with their extension. If the tag is not well-formed, the only item of the chain is the original tag.
+pub fn language_tag_chain(tag: &str) -> Vec<String> {
+    let Some(normalized) = normalize_language_tag(tag) else {
+        return vec![tag.to_owned()];
+    };
+
+    let mut subtags = normalized.split('-').collect::<Vec<_>>();
+    let mut chain = Vec::<String>::with_capacity(subtags.len());
+    while !subtags.is_empty() {
+        chain.push(subtags.join("-"));
+        subtags.pop();
+        while subtags.len() > 1 && subtags.last().map(|subtag| subtag.len() == 1).unwrap_or(false) {
+            subtags.pop();
+        }
+    }
+    chain
+}
+
+
+/// Get direction of the text (`ltr` or `rtl`) written in the language.
+///
+/// The script subtag has precedence (`az-Arab` is `rtl`, `ku-Latn` is `ltr`). Otherwise the direction is given by the
+/// default script of the language.
+pub fn text_direction(tag: &str) -> &'static str {
+    const RTL_SCRIPTS: &[&str] = &["adlm", "arab", "hebr", "nkoo", "rohg", "syrc", "thaa"];
+    const RTL_LANGUAGES: &[&str] = &["ar", "ckb", "dv", "fa", "he", "iw", "ks", "ps", "sd", "syr", "ug", "ur", "yi"];
+
+    let mut subtags = tag.split(['-', '_']);
+    let language = subtags.next().unwrap_or_default().to_ascii_lowercase();
+    let script = subtags
+        .take_while(|subtag| subtag.len() > 1)
+        .find(|subtag| subtag.len() == 4 && subtag.chars().all(|c| c.is_ascii_alphabetic()))
+        .map(|subtag| subtag.to_ascii_lowercase());
+    let rtl = match script {
+        Some(script) => RTL_SCRIPTS.contains(&script.as_str()),
+        None => RTL_LANGUAGES.contains(&language.as_str()),
+    };
+    if rtl { "rtl" } else { "ltr" }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn language_preference() {
+        let preference = LanguagePreference::new("sk").with_fallback("cs").with_fallbacks(["en", "de"]);
+        assert_eq!(preference.languages(), ["sk", "cs", "en", "de"]);
+        assert_eq!(preference, ["sk", "cs", "en", "de"].into_iter().collect());
+        assert_eq!(LanguagePreference::from("sk").languages(), ["sk"]);
+        assert!(LanguagePreference::default().is_empty());
+    }
+
+    #[test]
+    fn accept_language() {
+        assert_eq!(
+            parse_accept_language("cs-CZ;q=0.9, en;q=0.8,de, *;q=0.5, fr;q=0, pl;q=abc, sk;Q=0.9"),
+            vec![
+                ("de".to_owned(), 1.0),
+                ("cs-CZ".to_owned(), 0.9),
+                ("sk".to_owned(), 0.9),
+                ("en".to_owned(), 0.8),
+            ]
+        );
+        assert_eq!(parse_accept_language(""), vec![]);
+
+        let preference = LanguagePreference::from_accept_language("en;q=0.5, cs");
+        assert_eq!(preference.languages(), ["cs", "en"]);
+        assert_eq!(preference.primary(), Some("cs"));
+    }
+
+    #[test]
+    fn normalize_tags() {
+        assert_eq!(normalize_language_tag("EN"), Some("en".to_owned()));
+        assert_eq!(normalize_language_tag("en_us"), Some("en-US".to_owned()));
+        assert_eq!(normalize_language_tag("ZH-hant-tw"), Some("zh-Hant-TW".to_owned()));
+        assert_eq!(normalize_language_tag("es-419"), Some("es-419".to_owned()));
+        assert_eq!(normalize_language_tag("de-CH-1901"), Some("de-CH-1901".to_owned()));
+        assert_eq!(normalize_language_tag("en-a-BBB-x-Priv"), Some("en-a-bbb-x-priv".to_owned()));
+    }
+
+    #[test]
+    fn reject_malformed_tags() {
+        assert_eq!(normalize_language_tag(""), None);
+        assert_eq!(normalize_language_tag("e"), None);
+        assert_eq!(normalize_language_tag("en--US"), None);
+        assert_eq!(normalize_language_tag("en-US-"), None);
+        assert_eq!(normalize_language_tag("en-toolongsubtag"), None);
+        assert_eq!(normalize_language_tag("en-x"), None);
+        assert_eq!(normalize_language_tag("čeština"), None);
+    }
+
+    #[test]
+    fn tag_chain() {
+        assert_eq!(language_tag_chain("de-latn-at"), vec!["de-Latn-AT", "de-Latn", "de"]);
+        assert_eq!(language_tag_chain("pt-BR"), vec!["pt-BR", "pt"]);
+        assert_eq!(language_tag_chain("en-a-bbb-ccc"), vec!["en-a-bbb-ccc", "en-a-bbb", "en"]);
+        assert_eq!(language_tag_chain("EN"), vec!["en"]);
+        assert_eq!(language_tag_chain("not a tag"), vec!["not a tag"]);
+    }
+
+    #[test]
+    fn direction() {
+        assert_eq!(text_direction("ar-EG"), "rtl");
+        assert_eq!(text_direction("HE"), "rtl");
+        assert_eq!(text_direction("az-Arab"), "rtl");
+        assert_eq!(text_direction("ku-Latn"), "ltr");
+        assert_eq!(text_direction("en-US"), "ltr");
+        assert_eq!(text_direction(""), "ltr");
+    }
+}
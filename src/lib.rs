@@ -27,6 +27,330 @@
 //! Render result of the template group render is `Result<HashMap<String, String>, TerariumError>` Where the `HashMap`
 //! contains the data. Keys of the hashmap is group member keys and values are their rendered contents.
 //!
+//! Members of the group keep the order in which they were added. `render_group_ordered` returns the `RenderedGroup`
+//! with members in this order, so logs, snapshots and multipart messages are stable between runs. Batch renders and
+//! `GroupRenderReport` keep this order as well. Groups of the manifest keep the order of the manifest file and groups
+//! loaded by the `DirectoryLoader` are sorted by member keys.
+//!
+//! ## Fallback chains
+//!
+//! Besides single fallback language, the `render_template_with_preference` and `render_group_with_preference` methods
+//! accept the `LanguagePreference` - ordered list of languages tried in turn (e.g. `sk`, `cs`, `en`). Default fallback
+//! chains can be configured per template or group by `TerariumBuilder::set_template_fallbacks()` and
+//! `TerariumBuilder::set_group_fallbacks()`. Languages requested by the caller are tried first, then the group fallbacks
+//! and then the template fallbacks.
+//!
+//! The `render_group_consistent` method renders all members of the group in the same language - the first preferred
+//! language available for every member. If there is no such language, the `NoCommonLanguage` error is returned. Only the
+//! preference and the group fallbacks are searched (fallbacks of member templates could select different languages), and
+//! members with the language-agnostic content are compatible with any language.
+//!
+//! ```rust,no_run
+//! # use tera::Context;
+//! # use terarium::{LanguagePreference, Terarium};
+//! # let terarium = Terarium::default();
+//! let preference = LanguagePreference::new("sk").with_fallback("cs").with_fallback("en");
+//! let rendered = terarium.render_group_with_preference(&Context::new(), "greet_email", &preference);
+//! ```
+//!
+//! ## Accept-Language negotiation
+//!
+//! The `negotiate_template` and `negotiate_group` methods parse the HTTP `Accept-Language` header (with q-values) and
+//! negotiate it against languages available for the template or for all members of the group. Result is the
+//! `LanguagePreference` which can be passed to rendering directly or its `primary()` language used as the best match.
+//! The `template_languages` and `group_languages` methods return available languages.
+//!
+//! ## Render details
+//!
+//! The `render_template_detailed` and `render_group_detailed` methods return the `RenderOutcome` (per member for groups)
+//! with the rendered content, the resolved language (e.g. for the `Content-Language` header), the name of the internal
+//! Tera template and the `fallback_used` flag set when the primary language was not available.
+//!
+//! ## Missing translations
+//!
+//! Observer set by `TerariumBuilder::set_translation_observer()` is notified about every fallback (template rendered, but
+//! not in the primary requested language) and every miss (no content for any requested language). The `TranslationEvent`
+//! contains the template key, the requested language and the resolved language, so missing translations can be counted
+//! and ranked by frequency.
+//!
+//! ```rust,no_run
+//! # use std::collections::HashMap;
+//! # use std::sync::{Arc, Mutex};
+//! # use terarium::TerariumBuilder;
+//! let missing = Arc::new(Mutex::new(HashMap::<(String, String), usize>::new()));
+//! let counter = missing.clone();
+//! let mut builder = TerariumBuilder::default();
+//! builder.set_translation_observer(move |event| {
+//!     let key = (event.template_key.to_owned(), event.requested_language.unwrap_or_default().to_owned());
+//!     *counter.lock().unwrap().entry(key).or_default() += 1;
+//! });
+//! ```
+//!
+//! ## Locale filters
+//!
+//! The `format_number`, `format_currency` and `format_date` filters are registered automatically. They format values by
+//! the locale of the language resolved for the current render (e.g. `1 234,5` for `cs` and `1,234.5` for `en`). The
+//! language is read from the `__lang` variable of the render context, so the filters work the same in any thread. The
+//! `lang` argument overrides the language (e.g. `lang="de"`).
+//!
+//! ```jinja
+//! {{ amount | format_number(decimals=2) }}
+//! {{ amount | format_currency(currency="EUR") }}
+//! {{ due_date | format_date(format="long") }}
+//! ```
+//!
+//! Locale data are bundled with the library for `en`, `cs`, `sk`, `de`, `fr`, `es`, `it`, `pl`, `pt` and `nl` only.
+//! Other languages are formatted by the `en` locale and every render of template using the filters in such language is
+//! reported to the translation observer as the `UnsupportedLocale` event.
+//!
+//! The `format_date` filter accepts `YYYY-MM-DD` dates, RFC 3339 timestamps (time is ignored) and Unix timestamps between
+//! years 1 and 9999. The format is `short`, `medium` (default), `long`, `full` or custom pattern like `d. M. y`.
+//!
+//! The `plural` function selects text by the CLDR plural category (`zero`, `one`, `two`, `few`, `many` or `other`) of
+//! the `count` in the render language (or in the `lang` language). The `other` text is required and used when text of
+//! the category is missing.
+//!
+//! ```jinja
+//! {{ count }} {{ plural(count=count, one="zpráva", few="zprávy", many="zprávy", other="zpráv") }}
+//! ```
+//!
+//! ## Language variables
+//!
+//! Rendered templates can access reserved variables inserted into the context: `__lang` (language of the render),
+//! `__requested_lang` (primary requested language), `__fallback_used` (the primary language was not available) and
+//! `__dir` (`ltr` or `rtl` direction of the language given by `text_direction()`). Context variables with the same names
+//! are overwritten.
+//!
+//! ```jinja
+//! <html lang="{{ __lang }}" dir="{{ __dir }}">
+//! ```
+//!
+//! ## Parallel rendering
+//!
+//! With the `rayon` feature enabled, the `render_group_par` method renders members of the group in parallel by the
+//! `rayon` thread pool. The result and errors are the same as for `render_group_with_preference`.
+//!
+//! ## Async rendering
+//!
+//! With the `tokio` feature enabled, the `AsyncTerarium` facade renders templates and groups on the tokio blocking thread
+//! pool, so large renders do not block the executor. The facade is created from `Arc<Terarium>` (e.g. the `current()`
+//! snapshot of the `ReloadableTerarium`) and accepts owned arguments. Renders cancelled by the runtime shutdown return
+//! the `TerariumError::RenderCancelled` error.
+//!
+//! ```rust,ignore
+//! let terarium = AsyncTerarium::from(reloadable.current());
+//! let rendered = terarium.render_group(context, "greet_email", "cs").await?;
+//! ```
+//!
+//! ## Rendering into writer
+//!
+//! The `render_template_to` method renders the template into any `std::io::Write` sink without allocating the whole
+//! output. The `render_group_to` method writes each member into a writer created by the factory for the member key (e.g.
+//! a file per member). Contents of all members are resolved before the first writer is created.
+//!
+//! ```rust,no_run
+//! # use std::fs::File;
+//! # use tera::Context;
+//! # use terarium::Terarium;
+//! # let terarium = Terarium::default();
+//! terarium.render_template_to(&Context::new(), "report", "cs", Some("en"), File::create("report.html").unwrap()).unwrap();
+//! terarium.render_group_to(&Context::new(), "export", "cs", None, |member| File::create(format!("{}.txt", member))).unwrap();
+//! ```
+//!
+//! ## Partial group results
+//!
+//! The `render_group_partial` method renders every member of the group even if some of them fail. The returned
+//! `GroupRenderReport` contains rendered contents of the successful members and errors of the failed ones (both in
+//! order of the group definition), so one broken template does not block the others and all failures can be logged at
+//! once.
+//!
+//! ## Optional group members
+//!
+//! Members added by `TemplateGroupBuilder::add_optional_member()` are optional. When the template of an optional member
+//! has no content for requested languages, the member is left out of the result instead of failing the whole group (e.g.
+//! `preheader` existing only for some languages). Optional members do not restrict languages of `render_group_consistent`
+//! and `group_languages`.
+//!
+//! ```rust,no_run
+//! # use terarium::TemplateGroupBuilder;
+//! let group = TemplateGroupBuilder::default()
+//!     .add_member("subject".to_owned(), "greet_subject".to_owned())
+//!     .add_optional_member("preheader".to_owned(), "greet_preheader".to_owned())
+//!     .build();
+//! ```
+//!
+//! ## Context overlays
+//!
+//! Template group can define context overlays merged on top of the caller's context: one for the whole group
+//! (`TemplateGroupBuilder::set_context()`) and one for each member (`TemplateGroupBuilder::set_member_context()`). The
+//! merge order is deterministic: the caller's context, the group context, the member context and the reserved language
+//! variables. Later layers overwrite variables of the earlier ones.
+//!
+//! ```rust,no_run
+//! # use terarium::TemplateGroupBuilder;
+//! # use terarium::tera::Context;
+//! let mut html_context = Context::new();
+//! html_context.insert("tracking", &true);
+//! let mut text_context = Context::new();
+//! text_context.insert("tracking", &false);
+//! let group = TemplateGroupBuilder::default()
+//!     .add_member("html".to_owned(), "greet_html".to_owned())
+//!     .add_member("text".to_owned(), "greet_text".to_owned())
+//!     .set_member_context("html".to_owned(), html_context)
+//!     .set_member_context("text".to_owned(), text_context)
+//!     .build();
+//! ```
+//!
+//! ## Rendering in all languages
+//!
+//! `Terarium::render_template_all_languages()` and `Terarium::render_group_all_languages()` render the template or the
+//! group in every language available for it (e.g. for previews and translator review). Results are keyed by
+//! language. The languages come from the loaded templates, so the caller does not need to maintain them.
+//!
+//! Fallbacks are not applied and the language-agnostic content of the template is not included. The group is rendered in
+//! every language of any of its members and each language has its `GroupRenderReport`, so members missing in the language
+//! are reported as failed with the `LanguageNotFound` error.
+//!
+//! ## Batch rendering
+//!
+//! `Terarium::render_group_batch()` renders the group for each item of the batch (e.g. the same email for many
+//! recipients). Items are pairs of the context and the language preference, and results are returned in the same order.
+//! Member languages are resolved once for each distinct preference, the translation observer is still notified for
+//! every item. With the `rayon` feature,
+//! `Terarium::render_group_batch_par()` renders the items in parallel.
+//!
+//! ```rust,no_run
+//! # use terarium::{LanguagePreference, Terarium};
+//! # use terarium::tera::Context;
+//! # let terarium: Terarium = unimplemented!();
+//! let recipients = vec![
+//!     (Context::new(), LanguagePreference::new("cs").with_fallback("en")),
+//!     (Context::new(), LanguagePreference::new("en")),
+//! ];
+//! for result in terarium.render_group_batch("greet", recipients).unwrap() {
+//!     let rendered = result.unwrap();
+//! }
+//! ```
+//!
+//! ## Language-agnostic contents
+//!
+//! Content without languages (created by `Content::new_wildcard()`) is language-agnostic. It is used when no content
+//! of the template matches the requested language or its fallbacks, e.g. for a logo or a plain-text signature shared by
+//! all languages. Each template can have at most one language-agnostic content. Members with language-agnostic content do
+//! not restrict languages of the group in `render_group_consistent` and `group_languages`.
+//!
+//! ## Language tags
+//!
+//! By default, language keys are opaque strings. Call `TerariumBuilder::set_language_tags(true)` to handle them as BCP 47
+//! language tags. Language keys of contents and requested languages are normalized (`EN-us` is `en-US`) and the
+//! resolution walks from the most specific tag to the least specific one (`pt-BR` to `pt`) before the fallback language is
+//! tried.
+//!
+//! ## Loading templates from directory
+//!
+//! Templates can be loaded from a directory tree by the `DirectoryLoader`. Each file has to be named
+//! `<name>.<language>.<extension>`. Files are grouped into templates by their relative path without language and extension
+//! (e.g. `emails/welcome/subject.en.txt` and `emails/welcome/subject.cs.txt` are contents of the `emails/welcome/subject`
+//! template). Each directory with templates becomes a group (e.g. `emails/welcome` group with members `subject`, `html`,
+//! ...). Relative path of the file is used as the content name, so files can be included by `{% include %}` statement.
+//!
+//! Files named `<name>.<extension>` are language-agnostic contents. Only files with template extensions
+//! (`DirectoryLoader::DEFAULT_EXTENSIONS`: `html`, `htm`, `txt`, `tera`, `svg` and `xml`) are loaded, other files in the
+//! tree (e.g. `README.md`) are skipped. `with_extensions()` replaces the default extensions.
+//!
+//! ```rust,no_run
+//! use terarium::DirectoryLoader;
+//!
+//! let loader = DirectoryLoader::new("templates").with_extensions(["txt", "html"]);
+//! let terarium = loader.load().unwrap().build().unwrap();
+//! ```
+//!
+//! ## Manifest
+//!
+//! With the `manifest` feature, the whole catalog can be described by the `Manifest` struct deserialized with `serde`.
+//! Features `toml`, `json` and `yaml` add helper constructors for these formats (`Manifest::from_toml_str()`,
+//! `Manifest::from_file()`, ...).
+//!
+//! ```toml
+//! [[templates.greet_subject]]
+//! content = "Greetings from {{sender}}"
+//! languages = ["en"]
+//!
+//! [[templates.greet_subject]]
+//! content = "Pozdrav od {{sender}}"
+//! languages = ["cs"]
+//!
+//! [groups.greet_email]
+//! subject = "greet_subject"
+//! preheader = { template = "greet_preheader", optional = true }
+//! ```
+//!
+//! Group members are given by the template key or by the table with the `template` key and the `optional` flag.
+//!
+//! ## Embedding templates
+//!
+//! The `terarium-macros` crate provides the `embed_directory!` and `embed_manifest!` macros. They embed the template
+//! directory or manifest into the binary at compile time and expand into the `TerariumBuilder`. The catalog is built
+//! during compilation, so Tera syntax errors are reported by `cargo build`.
+//!
+//! ```rust,ignore
+//! use terarium_macros::embed_directory;
+//!
+//! let terarium = embed_directory!("templates").build().unwrap();
+//! ```
+//!
+//! ## Typed groups
+//!
+//! The `#[derive(TerariumGroup)]` macro of the `terarium-macros` crate maps fields of a struct to group members (fields of
+//! the `Option` type are optional members). `TerariumBuilder::require_group()` checks that the group has the members when
+//! the instance is built, and `Terarium::render_group_as()` renders the group into the struct.
+//!
+//! ```rust,ignore
+//! use terarium_macros::TerariumGroup;
+//!
+//! #[derive(TerariumGroup)]
+//! struct Email {
+//!     subject: String,
+//!     text: String,
+//!     #[terarium(member = "html_body")]
+//!     html: Option<String>,
+//! }
+//!
+//! builder.require_group::<Email>("welcome".to_owned());
+//! let terarium = builder.build().unwrap();
+//! let email: Email = terarium.render_group_as(&context, "welcome", &"en".into()).unwrap();
+//! ```
+//!
+//! ## Template sources
+//!
+//! Templates and groups can be loaded from any storage implementing the `TemplateSource` trait by the
+//! `TerariumBuilder::add_source()` method. The `DirectoryLoader`, `Manifest` and in-memory `MemorySource` implement this
+//! trait. The `ReloadableTerarium::from_source()` reloads templates from the source.
+//!
+//! ## Hot reloading
+//!
+//! The `ReloadableTerarium` handle holds the live `Terarium` instance and rebuilds it from template sources (directory,
+//! manifest or any custom loader) on `reload()`. The new instance is atomically swapped with the live one. When the rebuild
+//! fails, the last good instance keeps serving and the error is passed to the error callback. Renders use snapshot returned
+//! by `current()`, so already running renders finish on the old instance. The `watch()` method starts a background thread
+//! reloading the instance whenever the directory or the manifest file changes (`watch_paths()` watches explicit paths of
+//! custom sources).
+//!
+//! ```rust,no_run
+//! use std::error::Error;
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//! use terarium::ReloadableTerarium;
+//!
+//! let reloadable = Arc::new(
+//!     ReloadableTerarium::from_directory("templates")
+//!         .unwrap()
+//!         .with_error_callback(|err| eprintln!("Template reload failed: {}: {:?}", err, err.source()))
+//! );
+//! let _watcher = reloadable.watch(Duration::from_secs(1));
+//! let terarium = reloadable.current();
+//! ```
+//!
 //! ## Example
 //!
 //! ```rust
@@ -73,8 +397,8 @@
 //!     ctx.insert("sender", "Jara Cimrman");
 //!     ctx.insert("username", "Karel Capek");
 //!
-//!     let rendered_group_en = terarium.render_group(&ctx, "greet_email", "en", None).unwrap();
-//!     let rendered_group_cs = terarium.render_group(&ctx, "greet_email", "cs", None).unwrap();
+//!     let rendered_group_en = terarium.render_group_ordered(&ctx, "greet_email", &"en".into()).unwrap();
+//!     let rendered_group_cs = terarium.render_group_ordered(&ctx, "greet_email", &"cs".into()).unwrap();
 //!
 //!     println!("\nEnglish");
 //!     println!("=======\n");
@@ -92,16 +416,16 @@
 //! English
 //! =======
 //!
-//! text: Hello Karel Capek
 //! subject: Greetings from Jara Cimrman
+//! text: Hello Karel Capek
 //! html: <p>Hello Karel Capek</p>
 //!
 //! Czech
 //! =====
 //!
-//! html: <p>Nazdar Karel Capek</p>
 //! subject: Pozdrav od Jara Cimrman
 //! text: Nazdar Karel Capek
+//! html: <p>Nazdar Karel Capek</p>
 //!
 //! ```
 //!
@@ -110,9 +434,34 @@
 
 // re-export
 pub use tera;
+#[cfg(feature = "manifest")]
+pub use indexmap;
 
+#[cfg(feature = "tokio")]
+pub use asynchronous::*;
+pub use language::*;
+pub use loader::*;
+#[cfg(feature = "manifest")]
+pub use manifest::*;
+pub use observer::*;
+pub use outcome::*;
+pub use reload::*;
+pub use source::*;
 pub use templates::*;
+pub use typed::*;
 pub use crate::terarium::*;
 
+#[cfg(feature = "tokio")]
+mod asynchronous;
+mod language;
+mod loader;
+mod locale;
+#[cfg(feature = "manifest")]
+mod manifest;
+mod observer;
+mod outcome;
+mod reload;
+mod source;
 mod templates;
 mod terarium;
+mod typed;
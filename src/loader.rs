@@ -0,0 +1,413 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Error as IoError;
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+use crate::{
+    Content, SourceResult, Template, TemplateError, TemplateGroup, TemplateSource, TerariumBuilder, TerariumBuilderError,
+};
+
+/// Load templates and template groups from a directory tree.
+///
+/// Each file in the tree has to be named `<name>.<language>.<extension>` (e.g. `subject.en.txt`). Files are grouped
+/// into templates by their relative path without the language and extension parts, so `emails/welcome/subject.en.txt`
+/// and `emails/welcome/subject.cs.txt` are two contents of the `emails/welcome/subject` template. The relative path of
+/// the file is used as the content name, so the file can be referenced by the `{% include %}` statement. Files named
+/// `<name>.<extension>` (e.g. `logo.svg`) are language-agnostic contents used when no language matches.
+///
+/// Every directory (except the root one) containing at least one template becomes a template group. The group key is
+/// relative path of the directory and member keys are template names (e.g. the `emails/welcome` group with the
+/// `subject` member). Files of the directory have no order, so members of the group are sorted by member keys. Hidden
+/// files and directories (starting with `.`) are ignored. Only files with extensions of template types are loaded
+/// (see `DEFAULT_EXTENSIONS`), so other files kept next to templates (e.g. `README.md`) are ignored. Use the
+/// `with_extensions()` method to load other types.
+#[derive(Clone, Debug)]
+pub struct DirectoryLoader {
+    /// Root directory of the template tree.
+    root: PathBuf,
+    /// Extensions of template files.
+    extensions: Vec<String>,
+}
+
+
+impl DirectoryLoader {
+    /// Extensions of template files loaded by default.
+    pub const DEFAULT_EXTENSIONS: &'static [&'static str] = &["html", "htm", "txt", "tera", "svg", "xml"];
+
+    /// Create new loader reading templates with the `DEFAULT_EXTENSIONS` from the `root` directory.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into(), extensions: vec![] }.with_extensions(Self::DEFAULT_EXTENSIONS.iter().copied())
+    }
+
+    /// Load only files with one of the `extensions` (e.g. `txt` and `html`) instead of the `DEFAULT_EXTENSIONS`, other
+    /// files are ignored.
+    pub fn with_extensions<S: Into<String>>(mut self, extensions: impl IntoIterator<Item=S>) -> Self {
+        self.extensions = extensions.into_iter().map(|extension| extension.into()).collect();
+        self
+    }
+
+    /// Load templates and groups into new `TerariumBuilder`.
+    pub fn load(&self) -> Result<TerariumBuilder, LoaderError> {
+        let mut builder = TerariumBuilder::default();
+        self.load_into(&mut builder)?;
+        Ok(builder)
+    }
+
+    /// Load templates and groups into existing builder.
+    /// Templates and groups with same keys are replaced.
+    pub fn load_into(&self, builder: &mut TerariumBuilder) -> Result<(), LoaderError> {
+        let files = self.read_files()?;
+        let files = files.iter().map(|(path, content)| (path.as_str(), content.as_str())).collect::<Vec<_>>();
+        Self::load_files_into(&files, builder)
+    }
+
+    /// Load templates and groups from files given as pairs of relative path (with `/` as separator) and content.
+    /// The same naming rules as for files in the directory are applied. This is useful when the template files are
+    /// embedded into binary (e.g. by the `include_str!` macro).
+    pub fn load_embedded(files: &[(&str, &str)]) -> Result<TerariumBuilder, LoaderError> {
+        let mut builder = TerariumBuilder::default();
+        Self::load_files_into(files, &mut builder)?;
+        Ok(builder)
+    }
+
+    /// Get sorted list of template file paths relative to the root directory (with `/` as separator).
+    pub fn files(&self) -> Result<Vec<String>, LoaderError> {
+        let mut files = Vec::<String>::new();
+        self.scan_directory(&self.root, &mut files)?;
+        Ok(files)
+    }
+
+    /// Read all template files as pairs of relative path and content.
+    fn read_files(&self) -> Result<Vec<(String, String)>, LoaderError> {
+        let mut files = Vec::<(String, String)>::new();
+        for path in self.files()? {
+            let content = fs::read_to_string(self.root.join(&path))?;
+            files.push((path, content));
+        }
+        Ok(files)
+    }
+
+    /// Load templates and groups from pairs of relative path and content into the builder.
+    fn load_files_into(files: &[(&str, &str)], builder: &mut TerariumBuilder) -> Result<(), LoaderError> {
+        let groups = Self::collect_groups(files.iter().map(|(path, _)| *path))?;
+        for (key, template) in Self::collect_templates(files)? {
+            builder.add_template(key, template)?;
+        }
+        for (key, group) in groups {
+            builder.add_group(key, group.into_iter().collect::<TemplateGroup>())?;
+        }
+        Ok(())
+    }
+
+    /// Group contents of template files into templates.
+    fn collect_templates(files: &[(&str, &str)]) -> Result<BTreeMap<String, Template>, LoaderError> {
+        let mut templates = BTreeMap::<String, Template>::new();
+        for (path, content) in files {
+            let file = parse_file_path(path).ok_or_else(|| LoaderError::InvalidFileName(PathBuf::from(path)))?;
+            templates
+                .entry(file.template_key.clone())
+                .or_default()
+                .add_content(Content::new_named(content.to_string(), file.language.into_iter().collect(), path.to_string()))
+                .map_err(|err| LoaderError::InvalidTemplate(file.template_key, err))?;
+        }
+        Ok(templates)
+    }
+
+    /// Infer groups from directories of template files.
+    /// Members are collected into sorted maps, so the order of members does not depend on the order of `paths`.
+    fn collect_groups<'a>(
+        paths: impl Iterator<Item=&'a str>,
+    ) -> Result<BTreeMap<String, BTreeMap<String, String>>, LoaderError> {
+        let mut groups = BTreeMap::<String, BTreeMap<String, String>>::new();
+        for path in paths {
+            let file = parse_file_path(path).ok_or_else(|| LoaderError::InvalidFileName(PathBuf::from(path)))?;
+            if !file.directory.is_empty() {
+                groups.entry(file.directory).or_default().insert(file.template_name, file.template_key);
+            }
+        }
+        Ok(groups)
+    }
+
+    /// Recursively collect relative paths of template files in the `directory`.
+    fn scan_directory(&self, directory: &Path, files: &mut Vec<String>) -> Result<(), LoaderError> {
+        let mut entries = fs::read_dir(directory)?.collect::<Result<Vec<_>, _>>()?;
+        entries.sort_by_key(|entry| entry.file_name());
+
+        for entry in entries {
+            let path = entry.path();
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            if entry.file_type()?.is_dir() {
+                self.scan_directory(&path, files)?;
+            } else if self.is_template_file(&path) {
+                files.push(self.relative_path(&path)?);
+            }
+        }
+        Ok(())
+    }
+
+    /// Return `true` if the file has one of the template extensions.
+    fn is_template_file(&self, path: &Path) -> bool {
+        let extension = path.extension().and_then(|extension| extension.to_str()).unwrap_or_default();
+        self.extensions.iter().any(|k| k == extension)
+    }
+
+    /// Convert path of the file into path relative to the root directory with `/` as separator.
+    fn relative_path(&self, path: &Path) -> Result<String, LoaderError> {
+        let invalid_name = || LoaderError::InvalidFileName(path.to_owned());
+        let mut components = Vec::<&str>::new();
+        for component in path.strip_prefix(&self.root).map_err(|_| invalid_name())?.iter() {
+            components.push(component.to_str().ok_or_else(invalid_name)?);
+        }
+        Ok(components.join("/"))
+    }
+}
+
+
+impl TemplateSource for DirectoryLoader {
+    fn templates(&self) -> SourceResult<Vec<(String, Vec<Content>)>> {
+        let files = self.read_files()?;
+        let files = files.iter().map(|(path, content)| (path.as_str(), content.as_str())).collect::<Vec<_>>();
+        Ok(Self::collect_templates(&files)?
+            .into_iter()
+            .map(|(key, template)| (key, template.collect_contents()))
+            .collect())
+    }
+
+    fn groups(&self) -> SourceResult<Vec<(String, TemplateGroup)>> {
+        let files = self.files()?;
+        Ok(Self::collect_groups(files.iter().map(|path| path.as_str()))?
+            .into_iter()
+            .map(|(key, group)| (key, group.into_iter().collect()))
+            .collect())
+    }
+}
+
+
+/// Split relative path of the template file into the template key, the language and other parts.
+/// Return `None` if the file name matches neither the `<name>.<language>.<extension>` nor the `<name>.<extension>`
+/// pattern.
+fn parse_file_path(path: &str) -> Option<TemplateFile> {
+    let (directory, file_name) = path.rsplit_once('/').unwrap_or(("", path));
+
+    let name_parts = file_name.rsplitn(3, '.').collect::<Vec<_>>();
+    let (template_name, language) = match name_parts[..] {
+        [_extension, language, template_name] => (template_name, Some(language)),
+        [_extension, template_name] => (template_name, None),
+        _ => return None,
+    };
+    if template_name.is_empty() || language.map(|language| language.is_empty()).unwrap_or(false) {
+        return None;
+    }
+
+    let template_key = if directory.is_empty() {
+        template_name.to_owned()
+    } else {
+        format!("{}/{}", directory, template_name)
+    };
+
+    Some(TemplateFile {
+        directory: directory.to_owned(),
+        template_name: template_name.to_owned(),
+        template_key,
+        language: language.map(|language| language.to_owned()),
+    })
+}
+
+
+/// One template file found by the `DirectoryLoader`.
+struct TemplateFile {
+    /// Path of the parent directory relative to the root directory.
+    directory: String,
+    /// Name of the template (file name without language and extension).
+    template_name: String,
+    /// Key of the template the file belongs to.
+    template_key: String,
+    /// Language of the content (`None` for language-agnostic content).
+    language: Option<String>,
+}
+
+
+/// Errors returned by `DirectoryLoader`.
+#[derive(Debug, Error)]
+pub enum LoaderError {
+    /// Directory or file cannot be read.
+    #[error("Unable to read template files")]
+    Io(#[source] IoError),
+    /// Name of the file does not match the `<name>.<language>.<extension>` or `<name>.<extension>` pattern.
+    #[error("Invalid template file name {0}")]
+    InvalidFileName(PathBuf),
+    /// Loaded contents cannot form a template.
+    #[error("Unable to load template {0}")]
+    InvalidTemplate(String, #[source] TemplateError),
+    /// Loaded templates or groups were rejected by the builder.
+    #[error("Unable to add loaded templates into builder")]
+    BuilderError(#[source] TerariumBuilderError),
+}
+
+
+impl From<IoError> for LoaderError {
+    fn from(value: IoError) -> Self {
+        Self::Io(value)
+    }
+}
+
+
+impl From<TerariumBuilderError> for LoaderError {
+    fn from(value: TerariumBuilderError) -> Self {
+        Self::BuilderError(value)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use tempfile::TempDir;
+    use tera::Context;
+
+    use super::*;
+
+    #[test]
+    fn load_templates() {
+        let dir = make_directory(&[
+            ("emails/welcome/subject.en.txt", "Welcome {{name}}"),
+            ("emails/welcome/subject.cs.txt", "Vitejte {{name}}"),
+            ("emails/welcome/html.en.html", "<p>Welcome {{name}}</p>"),
+            ("footer.en.txt", "Bye"),
+        ]);
+        let terarium = DirectoryLoader::new(dir.path()).load().unwrap().build().unwrap();
+        let ctx = make_context();
+
+        assert_eq!(terarium.render_template(&ctx, "emails/welcome/subject", "cs", None).unwrap(), "Vitejte john");
+        assert_eq!(terarium.render_template(&ctx, "footer", "en", None).unwrap(), "Bye");
+
+        let group = terarium.render_group(&ctx, "emails/welcome", "en", None).unwrap();
+        assert_eq!(group.len(), 2);
+        assert_eq!(group["subject"], "Welcome john");
+        assert_eq!(group["html"], "<p>Welcome john</p>");
+    }
+
+    #[test]
+    fn include_by_path() {
+        let dir = make_directory(&[
+            ("partials/footer.en.txt", "Bye {{name}}"),
+            ("body.en.txt", "Hello {% include 'partials/footer.en.txt' %}"),
+        ]);
+        let terarium = DirectoryLoader::new(dir.path()).load().unwrap().build().unwrap();
+        assert_eq!(terarium.render_template(&make_context(), "body", "en", None).unwrap(), "Hello Bye john");
+    }
+
+    #[test]
+    fn ignore_hidden_files() {
+        let dir = make_directory(&[
+            ("subject.en.txt", "Hello"),
+            (".gitkeep", ""),
+            (".drafts/subject.en.txt", "Draft"),
+        ]);
+        let terarium = DirectoryLoader::new(dir.path()).load().unwrap().build().unwrap();
+        assert!(terarium.render_group(&make_context(), ".drafts", "en", None).is_err());
+        assert_eq!(terarium.render_template(&make_context(), "subject", "en", None).unwrap(), "Hello");
+    }
+
+    #[test]
+    fn load_embedded() {
+        let builder = DirectoryLoader::load_embedded(&[
+            ("emails/subject.en.txt", "Hello {{name}}"),
+            ("emails/subject.cs.txt", "Ahoj {{name}}"),
+        ]).unwrap();
+        let terarium = builder.build().unwrap();
+        let group = terarium.render_group(&make_context(), "emails", "cs", None).unwrap();
+        assert_eq!(group["subject"], "Ahoj john");
+    }
+
+    #[test]
+    fn directory_source() {
+        let dir = make_directory(&[("emails/subject.en.txt", "Hello {{name}}"), ("emails/subject.cs.txt", "Ahoj {{name}}")]);
+        let mut builder = TerariumBuilder::default();
+        builder.add_source(&DirectoryLoader::new(dir.path())).unwrap();
+        let terarium = builder.build().unwrap();
+        assert_eq!(terarium.render_group(&make_context(), "emails", "cs", None).unwrap()["subject"], "Ahoj john");
+    }
+
+    #[test]
+    fn list_files() {
+        let dir = make_directory(&[("b/subject.en.txt", ""), ("a.en.txt", ""), (".hidden.en.txt", "")]);
+        let files = DirectoryLoader::new(dir.path()).files().unwrap();
+        assert_eq!(files, vec!["a.en.txt".to_owned(), "b/subject.en.txt".to_owned()]);
+    }
+
+    #[test]
+    fn invalid_file_name() {
+        let result = DirectoryLoader::load_embedded(&[("subject", "Hello")]);
+        assert!(matches!(result.err().unwrap(), LoaderError::InvalidFileName(_)));
+
+        let dir = make_directory(&[("subject..txt", "Hello")]);
+        let result = DirectoryLoader::new(dir.path()).load();
+        assert!(matches!(result.err().unwrap(), LoaderError::InvalidFileName(_)));
+    }
+
+    #[test]
+    fn language_agnostic_file() {
+        let dir = make_directory(&[("emails/subject.txt", "Hi {{name}}"), ("emails/subject.cs.txt", "Ahoj {{name}}")]);
+        let terarium = DirectoryLoader::new(dir.path()).load().unwrap().build().unwrap();
+        assert_eq!(terarium.render_template(&make_context(), "emails/subject", "cs", None).unwrap(), "Ahoj john");
+        assert_eq!(terarium.render_template(&make_context(), "emails/subject", "de", None).unwrap(), "Hi john");
+    }
+
+    #[test]
+    fn filter_extensions() {
+        let files = [
+            ("subject.en.txt", "Hello"),
+            ("body.en.html", "<p>Hello</p>"),
+            ("README.md", "Templates"),
+            ("notes", ""),
+        ];
+        let dir = make_directory(&files);
+        let loader = DirectoryLoader::new(dir.path());
+        assert_eq!(loader.files().unwrap(), vec!["body.en.html", "subject.en.txt"]);
+        let terarium = loader.load().unwrap().build().unwrap();
+        assert!(terarium.template_languages("README").is_err());
+
+        let loader = DirectoryLoader::new(dir.path()).with_extensions(["txt", "md"]);
+        assert_eq!(loader.files().unwrap(), vec!["README.md", "subject.en.txt"]);
+    }
+
+    #[test]
+    fn duplicated_wildcard() {
+        let dir = make_directory(&[("logo.svg", "<svg/>"), ("logo.html", "<img/>")]);
+        let result = DirectoryLoader::new(dir.path()).load().unwrap().build();
+        assert!(matches!(result, Err(TerariumBuilderError::DuplicatedWildcardContent(key)) if key == "logo"));
+    }
+
+    #[test]
+    fn duplicated_language() {
+        let dir = make_directory(&[("subject.en.txt", "Hello"), ("subject.en.html", "<p>Hello</p>")]);
+        let result = DirectoryLoader::new(dir.path()).load();
+        assert!(matches!(
+            result.err().unwrap(),
+            LoaderError::InvalidTemplate(key, TemplateError::DuplicatedContentLanguages(_)) if key == "subject"
+        ));
+    }
+
+    fn make_directory(files: &[(&str, &str)]) -> TempDir {
+        let dir = TempDir::new().unwrap();
+        for (path, content) in files {
+            let path = dir.path().join(Path::new(path));
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        dir
+    }
+
+    fn make_context() -> Context {
+        let mut ctx = Context::new();
+        ctx.insert("name", "john");
+        ctx
+    }
+}
@@ -0,0 +1,843 @@
+use std::collections::HashMap;
+
+use tera::{Error as TeraError, Template, Tera, Value};
+use tera::ast::{Expr, ExprVal, FunctionCall, MacroDefinition, Node};
+
+use crate::language::language_tag_chain;
+
+/// Names of the locale-aware filters and functions.
+const LOCALE_FILTERS: &[&str] = &["format_number", "format_currency", "format_date", "plural"];
+
+/// Earliest Unix timestamp accepted by the `format_date` filter (`0001-01-01T00:00:00Z`).
+const MIN_TIMESTAMP: f64 = -62135596800.0;
+
+/// Latest Unix timestamp accepted by the `format_date` filter (`9999-12-31T23:59:59Z`).
+const MAX_TIMESTAMP: f64 = 253402300799.0;
+
+
+/// Locale data derived from the CLDR tables.
+struct Locale {
+    /// Language of the locale.
+    language: &'static str,
+    /// Decimal separator.
+    decimal: &'static str,
+    /// Grouping separator.
+    group: &'static str,
+    /// Minimal number of integer digits to use grouping separators.
+    min_grouping: usize,
+    /// Currency symbol is placed before the number.
+    currency_prefix: bool,
+    /// Separator between currency symbol and the number.
+    currency_space: &'static str,
+    /// Minus sign of negative amounts is placed between the currency symbol and the number (`€ -3,00`).
+    currency_sign_inside: bool,
+    /// Date patterns (short, medium, long and full).
+    date_patterns: [&'static str; 4],
+    /// Wide month names (format context).
+    months: [&'static str; 12],
+    /// Abbreviated month names (format context).
+    months_short: [&'static str; 12],
+    /// Wide weekday names starting with Monday.
+    weekdays: [&'static str; 7],
+    /// Cardinal plural rule.
+    plural_rule: fn(&PluralOperands) -> &'static str,
+}
+
+
+/// Plural operands of the number as defined by CLDR.
+struct PluralOperands {
+    /// Absolute value of the number.
+    n: f64,
+    /// Integer digits.
+    i: u64,
+    /// Number of visible fraction digits.
+    v: usize,
+}
+
+
+/// Bundled locales. Languages without a bundled locale are formatted by the first one (`en`) and reported to the
+/// translation observer by `Terarium`.
+const LOCALES: &[Locale] = &[
+    Locale {
+        language: "en",
+        decimal: ".",
+        group: ",",
+        min_grouping: 1,
+        currency_prefix: true,
+        currency_space: "",
+        currency_sign_inside: false,
+        date_patterns: ["M/d/yy", "MMM d, y", "MMMM d, y", "EEEE, MMMM d, y"],
+        months: [
+            "January", "February", "March", "April", "May", "June", "July", "August", "September", "October",
+            "November", "December",
+        ],
+        months_short: ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"],
+        weekdays: ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"],
+        plural_rule: plural_one_other,
+    },
+    Locale {
+        language: "cs",
+        decimal: ",",
+        group: "\u{a0}",
+        min_grouping: 1,
+        currency_prefix: false,
+        currency_space: "\u{a0}",
+        currency_sign_inside: false,
+        date_patterns: ["dd.MM.yy", "d. M. y", "d. MMMM y", "EEEE d. MMMM y"],
+        months: [
+            "ledna", "února", "března", "dubna", "května", "června", "července", "srpna", "září", "října",
+            "listopadu", "prosince",
+        ],
+        months_short: ["led", "úno", "bře", "dub", "kvě", "čvn", "čvc", "srp", "zář", "říj", "lis", "pro"],
+        weekdays: ["pondělí", "úterý", "středa", "čtvrtek", "pátek", "sobota", "neděle"],
+        plural_rule: plural_west_slavic,
+    },
+    Locale {
+        language: "sk",
+        decimal: ",",
+        group: "\u{a0}",
+        min_grouping: 1,
+        currency_prefix: false,
+        currency_space: "\u{a0}",
+        currency_sign_inside: false,
+        date_patterns: ["d. M. y", "d. M. y", "d. MMMM y", "EEEE d. MMMM y"],
+        months: [
+            "januára", "februára", "marca", "apríla", "mája", "júna", "júla", "augusta", "septembra", "októbra",
+            "novembra", "decembra",
+        ],
+        months_short: ["jan", "feb", "mar", "apr", "máj", "jún", "júl", "aug", "sep", "okt", "nov", "dec"],
+        weekdays: ["pondelok", "utorok", "streda", "štvrtok", "piatok", "sobota", "nedeľa"],
+        plural_rule: plural_west_slavic,
+    },
+    Locale {
+        language: "de",
+        decimal: ",",
+        group: ".",
+        min_grouping: 1,
+        currency_prefix: false,
+        currency_space: "\u{a0}",
+        currency_sign_inside: false,
+        date_patterns: ["dd.MM.yy", "dd.MM.y", "d. MMMM y", "EEEE, d. MMMM y"],
+        months: [
+            "Januar", "Februar", "März", "April", "Mai", "Juni", "Juli", "August", "September", "Oktober",
+            "November", "Dezember",
+        ],
+        months_short: [
+            "Jan.", "Feb.", "März", "Apr.", "Mai", "Juni", "Juli", "Aug.", "Sept.", "Okt.", "Nov.", "Dez.",
+        ],
+        weekdays: ["Montag", "Dienstag", "Mittwoch", "Donnerstag", "Freitag", "Samstag", "Sonntag"],
+        plural_rule: plural_one_other,
+    },
+    Locale {
+        language: "fr",
+        decimal: ",",
+        group: "\u{202f}",
+        min_grouping: 1,
+        currency_prefix: false,
+        currency_space: "\u{a0}",
+        currency_sign_inside: false,
+        date_patterns: ["dd/MM/y", "d MMM y", "d MMMM y", "EEEE d MMMM y"],
+        months: [
+            "janvier", "février", "mars", "avril", "mai", "juin", "juillet", "août", "septembre", "octobre",
+            "novembre", "décembre",
+        ],
+        months_short: [
+            "janv.", "févr.", "mars", "avr.", "mai", "juin", "juil.", "août", "sept.", "oct.", "nov.", "déc.",
+        ],
+        weekdays: ["lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi", "dimanche"],
+        plural_rule: plural_french,
+    },
+    Locale {
+        language: "es",
+        decimal: ",",
+        group: ".",
+        min_grouping: 2,
+        currency_prefix: false,
+        currency_space: "\u{a0}",
+        currency_sign_inside: false,
+        date_patterns: ["d/M/yy", "d MMM y", "d 'de' MMMM 'de' y", "EEEE, d 'de' MMMM 'de' y"],
+        months: [
+            "enero", "febrero", "marzo", "abril", "mayo", "junio", "julio", "agosto", "septiembre", "octubre",
+            "noviembre", "diciembre",
+        ],
+        months_short: ["ene", "feb", "mar", "abr", "may", "jun", "jul", "ago", "sept", "oct", "nov", "dic"],
+        weekdays: ["lunes", "martes", "miércoles", "jueves", "viernes", "sábado", "domingo"],
+        plural_rule: plural_romance,
+    },
+    Locale {
+        language: "it",
+        decimal: ",",
+        group: ".",
+        min_grouping: 1,
+        currency_prefix: false,
+        currency_space: "\u{a0}",
+        currency_sign_inside: false,
+        date_patterns: ["dd/MM/yy", "d MMM y", "d MMMM y", "EEEE d MMMM y"],
+        months: [
+            "gennaio", "febbraio", "marzo", "aprile", "maggio", "giugno", "luglio", "agosto", "settembre", "ottobre",
+            "novembre", "dicembre",
+        ],
+        months_short: ["gen", "feb", "mar", "apr", "mag", "giu", "lug", "ago", "set", "ott", "nov", "dic"],
+        weekdays: ["lunedì", "martedì", "mercoledì", "giovedì", "venerdì", "sabato", "domenica"],
+        plural_rule: plural_romance,
+    },
+    Locale {
+        language: "pl",
+        decimal: ",",
+        group: "\u{a0}",
+        min_grouping: 2,
+        currency_prefix: false,
+        currency_space: "\u{a0}",
+        currency_sign_inside: false,
+        date_patterns: ["d.MM.y", "d MMM y", "d MMMM y", "EEEE, d MMMM y"],
+        months: [
+            "stycznia", "lutego", "marca", "kwietnia", "maja", "czerwca", "lipca", "sierpnia", "września",
+            "października", "listopada", "grudnia",
+        ],
+        months_short: ["sty", "lut", "mar", "kwi", "maj", "cze", "lip", "sie", "wrz", "paź", "lis", "gru"],
+        weekdays: ["poniedziałek", "wtorek", "środa", "czwartek", "piątek", "sobota", "niedziela"],
+        plural_rule: plural_polish,
+    },
+    Locale {
+        language: "pt",
+        decimal: ",",
+        group: ".",
+        min_grouping: 1,
+        currency_prefix: true,
+        currency_space: "\u{a0}",
+        currency_sign_inside: false,
+        date_patterns: ["dd/MM/y", "d 'de' MMM 'de' y", "d 'de' MMMM 'de' y", "EEEE, d 'de' MMMM 'de' y"],
+        months: [
+            "janeiro", "fevereiro", "março", "abril", "maio", "junho", "julho", "agosto", "setembro", "outubro",
+            "novembro", "dezembro",
+        ],
+        months_short: [
+            "jan.", "fev.", "mar.", "abr.", "mai.", "jun.", "jul.", "ago.", "set.", "out.", "nov.", "dez.",
+        ],
+        weekdays: [
+            "segunda-feira", "terça-feira", "quarta-feira", "quinta-feira", "sexta-feira", "sábado", "domingo",
+        ],
+        plural_rule: plural_french,
+    },
+    Locale {
+        language: "nl",
+        decimal: ",",
+        group: ".",
+        min_grouping: 1,
+        currency_prefix: true,
+        currency_space: "\u{a0}",
+        currency_sign_inside: true,
+        date_patterns: ["dd-MM-y", "d MMM y", "d MMMM y", "EEEE d MMMM y"],
+        months: [
+            "januari", "februari", "maart", "april", "mei", "juni", "juli", "augustus", "september", "oktober",
+            "november", "december",
+        ],
+        months_short: ["jan", "feb", "mrt", "apr", "mei", "jun", "jul", "aug", "sep", "okt", "nov", "dec"],
+        weekdays: ["maandag", "dinsdag", "woensdag", "donderdag", "vrijdag", "zaterdag", "zondag"],
+        plural_rule: plural_one_other,
+    },
+];
+
+
+/// Currency symbols and number of fraction digits by ISO 4217 code. Other currencies use their code and 2 digits.
+const CURRENCIES: &[(&str, &str, usize)] = &[
+    ("CHF", "CHF", 2),
+    ("CZK", "Kč", 2),
+    ("EUR", "€", 2),
+    ("GBP", "£", 2),
+    ("JPY", "¥", 0),
+    ("PLN", "zł", 2),
+    ("USD", "$", 2),
+];
+
+
+/// Check there is a bundled locale for the language (or for any of its less specific tags).
+pub(crate) fn is_supported_language(language: &str) -> bool {
+    let chain = language_tag_chain(language);
+    chain.iter().any(|tag| LOCALES.iter().any(|locale| locale.language.eq_ignore_ascii_case(tag)))
+}
+
+
+/// Insert the `lang=__lang` argument into calls of the locale-aware filters and functions without the `lang`
+/// argument, so they use the render language. Return `true` if the template calls any of them.
+///
+/// Macros and blocks are stored by the Tera template separately from its AST, so they are updated as well.
+pub(crate) fn localize_template(template: &mut Template) -> bool {
+    let mut found = localize_nodes(&mut template.ast);
+    for definition in template.macros.values_mut() {
+        found |= localize_macro(definition);
+    }
+    for block in template.blocks.values_mut() {
+        found |= localize_nodes(&mut block.body);
+    }
+    for definitions in template.blocks_definitions.values_mut() {
+        for (_, block) in definitions.iter_mut() {
+            found |= localize_nodes(&mut block.body);
+        }
+    }
+    found
+}
+
+
+/// Localize calls in the template nodes. See `localize_template()` for details.
+fn localize_nodes(nodes: &mut [Node]) -> bool {
+    let mut found = false;
+    for node in nodes.iter_mut() {
+        found |= match node {
+            Node::VariableBlock(_, expr) => localize_expr(expr),
+            Node::MacroDefinition(_, definition, _) => localize_macro(definition),
+            Node::Set(_, set) => localize_expr(&mut set.value),
+            Node::FilterSection(_, section, _) => {
+                localize_call(&mut section.filter) | localize_nodes(&mut section.body)
+            }
+            Node::Block(_, block, _) => localize_nodes(&mut block.body),
+            Node::Forloop(_, forloop, _) => {
+                localize_expr(&mut forloop.container)
+                    | localize_nodes(&mut forloop.body)
+                    | forloop.empty_body.as_deref_mut().is_some_and(localize_nodes)
+            }
+            Node::If(condition, _) => {
+                let mut found = false;
+                for (_, expr, body) in condition.conditions.iter_mut() {
+                    found |= localize_expr(expr) | localize_nodes(body);
+                }
+                found | condition.otherwise.as_mut().is_some_and(|(_, body)| localize_nodes(body))
+            }
+            _ => false,
+        };
+    }
+    found
+}
+
+
+/// Localize calls in default values of arguments and in the body of the macro.
+fn localize_macro(definition: &mut MacroDefinition) -> bool {
+    let mut found = false;
+    for expr in definition.args.values_mut().flatten() {
+        found |= localize_expr(expr);
+    }
+    found | localize_nodes(&mut definition.body)
+}
+
+
+/// Localize calls in the expression and its filters.
+fn localize_expr(expr: &mut Expr) -> bool {
+    let mut found = localize_value(&mut expr.val);
+    for filter in expr.filters.iter_mut() {
+        found |= localize_call(filter);
+    }
+    found
+}
+
+
+/// Localize calls in the expression value.
+fn localize_value(value: &mut ExprVal) -> bool {
+    match value {
+        ExprVal::Math(math) => localize_expr(&mut math.lhs) | localize_expr(&mut math.rhs),
+        ExprVal::Logic(logic) => localize_expr(&mut logic.lhs) | localize_expr(&mut logic.rhs),
+        ExprVal::Test(test) => test.args.iter_mut().fold(false, |found, expr| localize_expr(expr) | found),
+        ExprVal::MacroCall(call) => call.args.values_mut().fold(false, |found, expr| localize_expr(expr) | found),
+        ExprVal::FunctionCall(call) => localize_call(call),
+        ExprVal::Array(items) => items.iter_mut().fold(false, |found, expr| localize_expr(expr) | found),
+        ExprVal::StringConcat(concat) => {
+            concat.values.iter_mut().fold(false, |found, value| localize_value(value) | found)
+        }
+        ExprVal::In(expr_in) => localize_expr(&mut expr_in.lhs) | localize_expr(&mut expr_in.rhs),
+        _ => false,
+    }
+}
+
+
+/// Localize the filter or function call and calls in its arguments.
+fn localize_call(call: &mut FunctionCall) -> bool {
+    let found = call.args.values_mut().fold(false, |found, expr| localize_expr(expr) | found);
+    if !LOCALE_FILTERS.contains(&call.name.as_str()) {
+        return found;
+    }
+    call.args.entry("lang".to_owned()).or_insert_with(|| Expr::new(ExprVal::Ident("__lang".to_owned())));
+    true
+}
+
+
+/// Register locale-aware filters and functions into the Tera instance.
+pub(crate) fn register_filters(tera: &mut Tera) {
+    tera.register_filter("format_number", format_number_filter);
+    tera.register_filter("format_currency", format_currency_filter);
+    tera.register_filter("format_date", format_date_filter);
+    tera.register_function("plural", plural_function);
+}
+
+
+/// Format number with the locale decimal and grouping separators.
+///
+/// Arguments: `lang` (language of the locale, the render language by default) and `decimals` (fixed number of
+/// fraction digits, up to 3 significant fraction digits by default).
+fn format_number_filter(value: &Value, args: &HashMap<String, Value>) -> tera::Result<Value> {
+    let locale = filter_locale(args, "Filter `format_number`")?;
+    let number = number_value(value, "format_number")?;
+    let formatted = match optional_usize(args, "decimals")? {
+        Some(decimals) => format_decimal(locale, number, decimals, false),
+        None => format_decimal(locale, number, 3, true),
+    };
+    Ok(Value::String(formatted))
+}
+
+
+/// Format amount of money in the `currency` (ISO 4217 code, required) by the locale rules.
+///
+/// Arguments: `currency`, `decimals` (overrides fraction digits of the currency) and `lang`.
+fn format_currency_filter(value: &Value, args: &HashMap<String, Value>) -> tera::Result<Value> {
+    let locale = filter_locale(args, "Filter `format_currency`")?;
+    let number = number_value(value, "format_currency")?;
+    let currency = args
+        .get("currency")
+        .and_then(|currency| currency.as_str())
+        .ok_or_else(|| TeraError::msg("Filter `format_currency` expected the `currency` argument"))?;
+    let (symbol, default_decimals) = CURRENCIES
+        .iter()
+        .find(|(code, _, _)| code.eq_ignore_ascii_case(currency))
+        .map(|(_, symbol, decimals)| (*symbol, *decimals))
+        .unwrap_or((currency, 2));
+    let decimals = optional_usize(args, "decimals")?.unwrap_or(default_decimals);
+
+    let amount = format_decimal(locale, number.abs(), decimals, false);
+    let sign = if number < 0.0 && amount.chars().any(|c| c.is_ascii_digit() && c != '0') { "-" } else { "" };
+    let formatted = if locale.currency_prefix && locale.currency_sign_inside {
+        format!("{}{}{}{}", symbol, locale.currency_space, sign, amount)
+    } else if locale.currency_prefix {
+        format!("{}{}{}{}", sign, symbol, locale.currency_space, amount)
+    } else {
+        format!("{}{}{}{}", sign, amount, locale.currency_space, symbol)
+    };
+    Ok(Value::String(formatted))
+}
+
+
+/// Format date by the locale pattern.
+///
+/// The value is the `YYYY-MM-DD` date (time and time zone of RFC 3339 timestamps are ignored) or the Unix timestamp
+/// in seconds (UTC) between years 1 and 9999. Arguments: `lang` and `format` (`short`, `medium` (default), `long`,
+/// `full` or custom pattern with `d`, `dd`, `M`, `MM`, `MMM`, `MMMM`, `y`, `yy`, `EEEE` fields and quoted literals).
+fn format_date_filter(value: &Value, args: &HashMap<String, Value>) -> tera::Result<Value> {
+    let locale = filter_locale(args, "Filter `format_date`")?;
+    let days = match value {
+        Value::Number(number) => number
+            .as_f64()
+            .filter(|timestamp| (MIN_TIMESTAMP..=MAX_TIMESTAMP).contains(timestamp))
+            .map(|timestamp| (timestamp / 86400.0).floor() as i64)
+            .ok_or_else(|| TeraError::msg(format!("Filter `format_date` received invalid timestamp `{}`", number)))?,
+        Value::String(date) => parse_date(date)
+            .ok_or_else(|| TeraError::msg(format!("Filter `format_date` received invalid date `{}`", date)))?,
+        _ => return Err(TeraError::msg("Filter `format_date` expected date string or timestamp")),
+    };
+    let format = args.get("format").and_then(|format| format.as_str()).unwrap_or("medium");
+    let pattern = match format {
+        "short" => locale.date_patterns[0],
+        "medium" => locale.date_patterns[1],
+        "long" => locale.date_patterns[2],
+        "full" => locale.date_patterns[3],
+        pattern => pattern,
+    };
+    Ok(Value::String(format_date(locale, days, pattern)))
+}
+
+
+/// Select text by the CLDR plural category of the `count` in the render language.
+///
+/// Arguments: `count`, texts of the categories (`zero`, `one`, `two`, `few`, `many` and `other`) and `lang` (the
+/// render language by default). The `other` text is required and it is used when the text of the selected category
+/// is missing.
+fn plural_function(args: &HashMap<String, Value>) -> tera::Result<Value> {
+    let locale = filter_locale(args, "Function `plural`")?;
+    let count = args.get("count").ok_or_else(|| TeraError::msg("Function `plural` expected the `count` argument"))?;
+    let operands = plural_operands(count)
+        .ok_or_else(|| TeraError::msg("Argument `count` of the `plural` function has to be number"))?;
+    let category = (locale.plural_rule)(&operands);
+    args.get(category)
+        .or_else(|| args.get("other"))
+        .cloned()
+        .ok_or_else(|| TeraError::msg("Function `plural` expected the `other` argument"))
+}
+
+
+/// Get plural operands of the number. Integers have no fraction digits, fraction digits of floats are given by their
+/// shortest representation (`1.5` has one fraction digit).
+fn plural_operands(value: &Value) -> Option<PluralOperands> {
+    let Value::Number(number) = value else {
+        return None;
+    };
+    if let Some(i) = number.as_u64().or_else(|| number.as_i64().map(|i| i.unsigned_abs())) {
+        return Some(PluralOperands { n: i as f64, i, v: 0 });
+    }
+    let n = number.as_f64()?.abs();
+    let formatted = format!("{:?}", n);
+    let v = formatted.split_once('.').map(|(_, fraction)| fraction.len()).unwrap_or(0);
+    Some(PluralOperands { n, i: n.trunc() as u64, v })
+}
+
+
+/// Plural rule of languages with `one` and `other` categories (English, German, Dutch).
+fn plural_one_other(operands: &PluralOperands) -> &'static str {
+    if operands.i == 1 && operands.v == 0 { "one" } else { "other" }
+}
+
+
+/// Plural rule of Czech and Slovak.
+fn plural_west_slavic(operands: &PluralOperands) -> &'static str {
+    match (operands.i, operands.v) {
+        (1, 0) => "one",
+        (2..=4, 0) => "few",
+        (_, 0) => "other",
+        _ => "many",
+    }
+}
+
+
+/// Plural rule of Polish.
+fn plural_polish(operands: &PluralOperands) -> &'static str {
+    let (i, v) = (operands.i, operands.v);
+    if v != 0 {
+        "other"
+    } else if i == 1 {
+        "one"
+    } else if (2..=4).contains(&(i % 10)) && !(12..=14).contains(&(i % 100)) {
+        "few"
+    } else {
+        "many"
+    }
+}
+
+
+/// Plural rule of French and Portuguese.
+fn plural_french(operands: &PluralOperands) -> &'static str {
+    if operands.i <= 1 {
+        "one"
+    } else if is_million_multiple(operands) {
+        "many"
+    } else {
+        "other"
+    }
+}
+
+
+/// Plural rule of Spanish and Italian.
+fn plural_romance(operands: &PluralOperands) -> &'static str {
+    if operands.n == 1.0 && operands.v == 0 {
+        "one"
+    } else if is_million_multiple(operands) {
+        "many"
+    } else {
+        "other"
+    }
+}
+
+
+/// Check the number is integer multiple of million (the `many` category of Romance languages).
+fn is_million_multiple(operands: &PluralOperands) -> bool {
+    operands.v == 0 && operands.i != 0 && operands.i % 1_000_000 == 0
+}
+
+
+/// Select locale by the `lang` argument of the filter or function.
+/// The argument is inserted by `localize_template()` when the template does not set it.
+fn filter_locale(args: &HashMap<String, Value>, name: &str) -> tera::Result<&'static Locale> {
+    let language = args.get("lang").ok_or_else(|| TeraError::msg(format!("{} expected the `lang` argument", name)))?;
+    let language = language.as_str().ok_or_else(|| TeraError::msg("Argument `lang` has to be string"))?;
+    Ok(find_locale(language))
+}
+
+
+/// Find the bundled locale matching the language (from the most specific tag to the least specific one).
+/// The English locale is used when no locale matches.
+fn find_locale(language: &str) -> &'static Locale {
+    language_tag_chain(language)
+        .iter()
+        .find_map(|tag| LOCALES.iter().find(|locale| locale.language.eq_ignore_ascii_case(tag)))
+        .unwrap_or(&LOCALES[0])
+}
+
+
+/// Get number from the filter input.
+fn number_value(value: &Value, filter: &str) -> tera::Result<f64> {
+    value.as_f64().ok_or_else(|| TeraError::msg(format!("Filter `{}` expected number", filter)))
+}
+
+
+/// Get optional non-negative integer argument.
+fn optional_usize(args: &HashMap<String, Value>, name: &str) -> tera::Result<Option<usize>> {
+    match args.get(name) {
+        Some(value) => value
+            .as_u64()
+            .map(|value| Some(value as usize))
+            .ok_or_else(|| TeraError::msg(format!("Argument `{}` has to be non-negative integer", name))),
+        None => Ok(None),
+    }
+}
+
+
+/// Format number with `decimals` fraction digits. Trailing zeros are removed when `trim` is set.
+fn format_decimal(locale: &Locale, number: f64, decimals: usize, trim: bool) -> String {
+    let formatted = format!("{:.*}", decimals, number.abs());
+    let (integer, fraction) = formatted.split_once('.').unwrap_or((&formatted, ""));
+    let fraction = if trim { fraction.trim_end_matches('0') } else { fraction };
+
+    let mut result = String::new();
+    if number < 0.0 && formatted.chars().any(|c| c.is_ascii_digit() && c != '0') {
+        result.push('-');
+    }
+    let use_grouping = integer.len() >= 3 + locale.min_grouping;
+    for (i, digit) in integer.chars().enumerate() {
+        if use_grouping && i > 0 && (integer.len() - i) % 3 == 0 {
+            result.push_str(locale.group);
+        }
+        result.push(digit);
+    }
+    if !fraction.is_empty() {
+        result.push_str(locale.decimal);
+        result.push_str(fraction);
+    }
+    result
+}
+
+
+/// Format date given by number of days since the Unix epoch by the CLDR-like pattern.
+fn format_date(locale: &Locale, days: i64, pattern: &str) -> String {
+    let (year, month, day) = civil_from_days(days);
+    let weekday = (days + 3).rem_euclid(7) as usize;
+    let chars = pattern.chars().collect::<Vec<_>>();
+    let mut result = String::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\'' {
+            let end = chars[i + 1..].iter().position(|c| *c == '\'').map(|p| i + 1 + p).unwrap_or(chars.len());
+            result.extend(&chars[i + 1..end]);
+            i = end + 1;
+            continue;
+        }
+
+        let count = chars[i..].iter().take_while(|other| **other == c).count();
+        match (c, count) {
+            ('d', 1) => result.push_str(&day.to_string()),
+            ('d', _) => result.push_str(&format!("{:02}", day)),
+            ('M', 1) => result.push_str(&month.to_string()),
+            ('M', 2) => result.push_str(&format!("{:02}", month)),
+            ('M', 3) => result.push_str(locale.months_short[month as usize - 1]),
+            ('M', _) => result.push_str(locale.months[month as usize - 1]),
+            ('y', 2) => result.push_str(&format!("{:02}", year.rem_euclid(100))),
+            ('y', _) => result.push_str(&year.to_string()),
+            ('E', _) => result.push_str(locale.weekdays[weekday]),
+            _ => (0..count).for_each(|_| result.push(c)),
+        }
+        i += count;
+    }
+    result
+}
+
+
+/// Parse the `YYYY-MM-DD` date (optionally followed by time) into number of days since the Unix epoch.
+fn parse_date(value: &str) -> Option<i64> {
+    let date = value.get(..10)?;
+    if value.len() > 10 && !value[10..].starts_with(['T', 't', ' ']) {
+        return None;
+    }
+    let mut parts = date.split('-');
+    let year = parts.next().filter(|part| part.len() == 4)?.parse::<i64>().ok()?;
+    let month = parts.next().filter(|part| part.len() == 2)?.parse::<i64>().ok()?;
+    let day = parts.next().filter(|part| part.len() == 2)?.parse::<i64>().ok()?;
+    if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+        return None;
+    }
+    Some(days_from_civil(year, month, day))
+}
+
+
+/// Get number of days in the month.
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+
+/// Convert date of the proleptic Gregorian calendar into number of days since the Unix epoch.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+
+/// Convert number of days since the Unix epoch into year, month and day.
+/// The days have to be in the range of timestamps accepted by the `format_date` filter.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use tera::Context;
+
+    use super::*;
+
+    #[test]
+    fn format_numbers() {
+        assert_eq!(render("{{ 1234567.891 | format_number }}", "en"), "1,234,567.891");
+        assert_eq!(render("{{ 1234567.891 | format_number }}", "cs"), "1\u{a0}234\u{a0}567,891");
+        assert_eq!(render("{{ 1234.5 | format_number(decimals=2) }}", "de-AT"), "1.234,50");
+        assert_eq!(render("{{ 1234 | format_number }}", "es"), "1234");
+        assert_eq!(render("{{ 12345 | format_number }}", "es"), "12.345");
+        assert_eq!(render("{{ -0.001 | format_number(decimals=2) }}", "en"), "0.00");
+        assert_eq!(render("{{ -1.5 | format_number }}", "en"), "-1.5");
+    }
+
+    #[test]
+    fn format_currencies() {
+        assert_eq!(render("{{ 1234.5 | format_currency(currency='USD') }}", "en"), "$1,234.50");
+        let czk = render("{{ 1234.5 | format_currency(currency='CZK') }}", "cs");
+        assert_eq!(czk, "1\u{a0}234,50\u{a0}Kč");
+        assert_eq!(render("{{ -3 | format_currency(currency='EUR') }}", "nl"), "€\u{a0}-3,00");
+        assert_eq!(render("{{ -3 | format_currency(currency='EUR') }}", "de"), "-3,00\u{a0}€");
+        assert_eq!(render("{{ 1500 | format_currency(currency='JPY') }}", "en"), "¥1,500");
+        assert_eq!(render("{{ 2 | format_currency(currency='XYZ') }}", "en"), "XYZ2.00");
+    }
+
+    #[test]
+    fn format_dates() {
+        assert_eq!(render("{{ '2024-03-05' | format_date }}", "en"), "Mar 5, 2024");
+        let long = render("{{ '2024-03-05T10:00:00Z' | format_date(format='long') }}", "cs");
+        assert_eq!(long, "5. března 2024");
+        let full = render("{{ '2024-03-05' | format_date(format='full') }}", "de");
+        assert_eq!(full, "Dienstag, 5. März 2024");
+        assert_eq!(render("{{ '2024-03-05' | format_date(format='long') }}", "es"), "5 de marzo de 2024");
+        assert_eq!(render("{{ 0 | format_date(format='short') }}", "en"), "1/1/70");
+        assert_eq!(render("{{ 951782400 | format_date(format='dd.MM.y') }}", "en"), "29.02.2000");
+    }
+
+    #[test]
+    fn override_language() {
+        assert_eq!(render("{{ 1.5 | format_number(lang='fr') }}", "en"), "1,5");
+        assert_eq!(render("{{ 1.5 | format_number(lang=other_lang) }}", "en"), "1,5");
+        assert_eq!(render("{{ 1.5 | format_number }}", "unknown"), "1.5");
+    }
+
+    #[test]
+    fn default_to_render_language() {
+        let template = "{% macro price(value) %}{{ value | format_currency(currency='EUR') }}{% endmacro price %}\
+            {% block count %}{% for n in [2] %}{{ plural(count=n, one='kus', few='kusy', other='kusů') }}{% endfor %}\
+            {% endblock count %} \
+            {% filter format_date(format='long') %}2024-03-05{% endfilter %} {{ self::price(value=3) }}";
+        assert_eq!(render(template, "cs"), "kusy 5. března 2024 3,00\u{a0}€");
+        assert_eq!(render(template, "en"), "kusů March 5, 2024 €3.00");
+    }
+
+    #[test]
+    fn invalid_input() {
+        let mut tera = Tera::default();
+        register_filters(&mut tera);
+        assert!(tera.render_str("{{ 'abc' | format_number(lang='en') }}", &Context::new()).is_err());
+        assert!(tera.render_str("{{ '2024-02-30' | format_date(lang='en') }}", &Context::new()).is_err());
+        assert!(tera.render_str("{{ 1e300 | format_date(lang='en') }}", &Context::new()).is_err());
+        assert!(tera.render_str("{{ -1e12 | format_date(lang='en') }}", &Context::new()).is_err());
+        assert!(tera.render_str("{{ 1 | format_currency(lang='en') }}", &Context::new()).is_err());
+        assert!(tera.render_str("{{ 1 | format_number }}", &Context::new()).is_err());
+    }
+
+    #[test]
+    fn timestamp_range() {
+        assert_eq!(render("{{ 253402300799 | format_date(format='dd.MM.y') }}", "en"), "31.12.9999");
+        assert_eq!(render("{{ -62135596800 | format_date(format='dd.MM.y') }}", "en"), "01.01.1");
+    }
+
+    #[test]
+    fn supported_languages() {
+        assert!(is_supported_language("cs"));
+        assert!(is_supported_language("de-AT"));
+        assert!(!is_supported_language("ja"));
+    }
+
+    #[test]
+    fn detect_locale_filters() {
+        let uses = |source: &str| localize_template(&mut Template::new("test", None, source).unwrap());
+        assert!(uses("{{ price | round | format_number }}"));
+        assert!(uses("{% if count > 1 %}{{ plural(count=count, other='a') }}{% endif %}"));
+        assert!(uses("{% for item in items %}{{ item | format_currency(currency='EUR', lang='en') }}{% endfor %}"));
+        assert!(!uses("{{ name | upper }} {{ __lang }}"));
+    }
+
+    #[test]
+    fn plural_categories() {
+        let template =
+            "{{ plural(count=count, one='zpráva', few='zprávy', many='zprávy', other='zpráv') }}";
+        let words = [1, 2, 4, 5, 11, 0].map(|count| render_count(template, "cs", count)).join(" ");
+        assert_eq!(words, "zpráva zprávy zprávy zpráv zpráv zpráv");
+        assert_eq!(render("{{ plural(count=1.5, one='a', few='b', many='c', other='d') }}", "cs"), "c");
+        assert_eq!(render("{{ plural(count=1, one='message', other='messages') }}", "en"), "message");
+        let template = "{{ plural(count=-1, one='message', other='messages') }}";
+        assert_eq!(render(template, "en-GB"), "message");
+        let template = "{{ plural(count=1.0, one='message', other='messages') }}";
+        assert_eq!(render(template, "en"), "messages");
+        assert_eq!(render("{{ plural(count=0, one='message', other='messages') }}", "fr"), "message");
+        assert_eq!(render("{{ plural(count=1000000, one='a', many='b', other='c') }}", "es"), "b");
+        assert_eq!(render("{{ plural(count=2, one='a', other='b', lang='en') }}", "cs"), "b");
+    }
+
+    #[test]
+    fn polish_plural_categories() {
+        let categories = [1, 2, 5, 12, 22, 25, 112]
+            .map(|count| plural_polish(&plural_operands(&Value::from(count)).unwrap()))
+            .join(" ");
+        assert_eq!(categories, "one few many many few many many");
+    }
+
+    #[test]
+    fn plural_fallback_to_other() {
+        assert_eq!(render("{{ plural(count=3, one='a', other='b') }}", "cs"), "b");
+        let mut tera = Tera::default();
+        register_filters(&mut tera);
+        assert!(tera.render_str("{{ plural(count=3, one='a', lang='en') }}", &Context::new()).is_err());
+        assert!(tera.render_str("{{ plural(count='x', other='a', lang='en') }}", &Context::new()).is_err());
+    }
+
+    #[test]
+    fn civil_dates() {
+        for days in [-800000, -1, 0, 59, 10957, 19787, 800000] {
+            let (year, month, day) = civil_from_days(days);
+            assert_eq!(days_from_civil(year, month, day), days);
+        }
+        assert_eq!(parse_date("2024-03-05"), Some(19787));
+    }
+
+    fn render(template: &str, language: &str) -> String {
+        render_count(template, language, 0)
+    }
+
+    fn render_count(template: &str, language: &str, count: i64) -> String {
+        let mut tera = Tera::default();
+        register_filters(&mut tera);
+        tera.add_raw_template("test", template).unwrap();
+        localize_template(tera.templates.get_mut("test").unwrap());
+        let mut ctx = Context::new();
+        ctx.insert("__lang", language);
+        ctx.insert("other_lang", "fr");
+        ctx.insert("count", &count);
+        tera.render("test", &ctx).unwrap()
+    }
+}
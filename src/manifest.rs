@@ -0,0 +1,388 @@
+use std::collections::BTreeMap;
+#[cfg(any(feature = "toml", feature = "json", feature = "yaml"))]
+use std::fs;
+use std::io::Error as IoError;
+use std::path::Path;
+
+use indexmap::IndexMap;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::{
+    Content, GroupMember, SourceResult, Template, TemplateError, TemplateGroup, TemplateSource, TerariumBuilder,
+    TerariumBuilderError,
+};
+
+/// Declarative description of the whole template catalog.
+///
+/// The manifest can be deserialized from any format supported by `serde`. Helper constructors for TOML, JSON and YAML
+/// are available behind the `toml`, `json` and `yaml` features. Example of the TOML manifest:
+///
+/// ```toml
+/// [[templates.greet_subject]]
+/// content = "Greetings from {{sender}}"
+/// languages = ["en"]
+///
+/// [[templates.greet_subject]]
+/// content = "Pozdrav od {{sender}}"
+/// languages = ["cs"]
+/// name = "greet_subject_cs"
+///
+/// [groups.greet_email]
+/// subject = "greet_subject"
+/// preheader = { template = "greet_preheader", optional = true }
+/// ```
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    /// Contents of templates by template key.
+    #[serde(default)]
+    pub templates: BTreeMap<String, Vec<Content>>,
+    /// Group members by member key by group key. Members keep the order of the manifest file.
+    #[serde(default)]
+    pub groups: BTreeMap<String, IndexMap<String, ManifestMember>>,
+}
+
+
+/// Group member entry of the manifest.
+///
+/// The entry is either the template key of the mandatory member (`subject = "greet_subject"`) or the table with the
+/// template key and the optional flag (`preheader = { template = "greet_preheader", optional = true }`).
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(from = "MemberEntry")]
+pub struct ManifestMember {
+    /// Key of the rendered template.
+    pub template: String,
+    /// The member is optional (see `GroupMember::optional`).
+    pub optional: bool,
+}
+
+
+impl ManifestMember {
+    /// Create new mandatory member.
+    pub fn new(template: String) -> Self {
+        Self { template, optional: false }
+    }
+
+    /// Create new optional member.
+    pub fn new_optional(template: String) -> Self {
+        Self { template, optional: true }
+    }
+}
+
+
+impl From<ManifestMember> for GroupMember {
+    fn from(value: ManifestMember) -> Self {
+        Self { template_key: value.template, optional: value.optional }
+    }
+}
+
+
+/// Serialized forms of the group member entry.
+#[derive(Deserialize)]
+#[serde(untagged, deny_unknown_fields)]
+enum MemberEntry {
+    /// Template key of the mandatory member.
+    Template(String),
+    /// Table with the template key and the optional flag.
+    Table {
+        template: String,
+        #[serde(default)]
+        optional: bool,
+    },
+}
+
+
+impl From<MemberEntry> for ManifestMember {
+    fn from(value: MemberEntry) -> Self {
+        match value {
+            MemberEntry::Template(template) => Self::new(template),
+            MemberEntry::Table { template, optional } => Self { template, optional },
+        }
+    }
+}
+
+
+impl Manifest {
+    /// Parse manifest from the TOML string.
+    #[cfg(feature = "toml")]
+    pub fn from_toml_str(source: &str) -> Result<Self, ManifestError> {
+        toml::from_str(source).map_err(|err| ManifestError::ParseError(err.to_string()))
+    }
+
+    /// Parse manifest from the JSON string.
+    #[cfg(feature = "json")]
+    pub fn from_json_str(source: &str) -> Result<Self, ManifestError> {
+        serde_json::from_str(source).map_err(|err| ManifestError::ParseError(err.to_string()))
+    }
+
+    /// Parse manifest from the YAML string.
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml_str(source: &str) -> Result<Self, ManifestError> {
+        serde_yaml::from_str(source).map_err(|err| ManifestError::ParseError(err.to_string()))
+    }
+
+    /// Read manifest from file. The format is selected by the file extension (`toml`, `json`, `yaml` or `yml`) and
+    /// the corresponding feature has to be enabled.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ManifestError> {
+        let path = path.as_ref();
+        match path.extension().and_then(|ext| ext.to_str()).unwrap_or_default() {
+            #[cfg(feature = "toml")]
+            "toml" => Self::from_toml_str(&Self::read_file(path)?),
+            #[cfg(feature = "json")]
+            "json" => Self::from_json_str(&Self::read_file(path)?),
+            #[cfg(feature = "yaml")]
+            "yaml" | "yml" => Self::from_yaml_str(&Self::read_file(path)?),
+            extension => Err(ManifestError::UnsupportedFormat(extension.to_owned())),
+        }
+    }
+
+    /// Create new `TerariumBuilder` with templates and groups from the manifest.
+    pub fn into_builder(self) -> Result<TerariumBuilder, ManifestError> {
+        let mut builder = TerariumBuilder::default();
+        self.load_into(&mut builder)?;
+        Ok(builder)
+    }
+
+    /// Add templates and groups from the manifest into existing builder.
+    /// Templates and groups with same keys are replaced.
+    pub fn load_into(self, builder: &mut TerariumBuilder) -> Result<(), ManifestError> {
+        for (key, contents) in self.templates {
+            let template = Template::new(contents).map_err(|err| ManifestError::InvalidTemplate(key.clone(), err))?;
+            builder
+                .add_template(key.clone(), template)
+                .map_err(|err| ManifestError::RejectedTemplate(key, err))?;
+        }
+        for (key, members) in self.groups {
+            builder
+                .add_group(key.clone(), make_group(members))
+                .map_err(|err| ManifestError::InvalidGroup(key, err))?;
+        }
+        Ok(())
+    }
+
+    /// Read content of the manifest file.
+    #[cfg(any(feature = "toml", feature = "json", feature = "yaml"))]
+    fn read_file(path: &Path) -> Result<String, ManifestError> {
+        fs::read_to_string(path).map_err(ManifestError::Io)
+    }
+}
+
+
+impl TemplateSource for Manifest {
+    fn templates(&self) -> SourceResult<Vec<(String, Vec<Content>)>> {
+        Ok(self.templates.iter().map(|(key, contents)| (key.clone(), contents.clone())).collect())
+    }
+
+    fn groups(&self) -> SourceResult<Vec<(String, TemplateGroup)>> {
+        Ok(self.groups.iter().map(|(key, members)| (key.clone(), make_group(members.clone()))).collect())
+    }
+}
+
+
+/// Create template group from the manifest members.
+fn make_group(members: IndexMap<String, ManifestMember>) -> TemplateGroup {
+    let mut group = TemplateGroup::default();
+    members.into_iter().for_each(|(member_key, member)| group.add_member(member_key, member.into()));
+    group
+}
+
+
+/// Errors returned when manifest is loaded.
+#[derive(Debug, Error)]
+pub enum ManifestError {
+    /// The manifest file cannot be read.
+    #[error("Unable to read manifest file")]
+    Io(#[source] IoError),
+    /// The manifest cannot be parsed.
+    #[error("Unable to parse manifest: {0}")]
+    ParseError(String),
+    /// Format of the manifest file is not known or its feature is not enabled.
+    #[error("Unsupported manifest format {0}")]
+    UnsupportedFormat(String),
+    /// Contents of the template entry cannot form a template.
+    #[error("Invalid template {0} in manifest")]
+    InvalidTemplate(String, #[source] TemplateError),
+    /// Template entry was rejected by the builder.
+    #[error("Template {0} in manifest was rejected by builder")]
+    RejectedTemplate(String, #[source] TerariumBuilderError),
+    /// Group entry was rejected by the builder.
+    #[error("Invalid group {0} in manifest")]
+    InvalidGroup(String, #[source] TerariumBuilderError),
+}
+
+
+#[cfg(test)]
+mod tests {
+    use tera::Context;
+
+    use super::*;
+
+    #[test]
+    fn build_from_manifest() {
+        let terarium = make_manifest().into_builder().unwrap().build().unwrap();
+        let mut ctx = Context::new();
+        ctx.insert("name", "john");
+
+        assert_eq!(terarium.render_template(&ctx, "subject", "cs", None).unwrap(), "Ahoj john");
+        let group = terarium.render_group(&ctx, "email", "en", None).unwrap();
+        assert_eq!(group["subject"], "Hello john");
+    }
+
+    #[test]
+    fn invalid_template_entry() {
+        let mut manifest = make_manifest();
+        manifest.templates.get_mut("subject").unwrap().push(Content::new("Hi".to_owned(), vec!["en".to_owned()]));
+        let result = manifest.into_builder();
+        assert!(matches!(
+            result.err().unwrap(),
+            ManifestError::InvalidTemplate(key, TemplateError::DuplicatedContentLanguages(_)) if key == "subject"
+        ));
+    }
+
+    #[test]
+    fn invalid_group_entry() {
+        let mut manifest = make_manifest();
+        let members = IndexMap::from([("text".to_owned(), ManifestMember::new("missing".to_owned()))]);
+        manifest.groups.insert("broken".to_owned(), members);
+        let result = manifest.into_builder();
+        assert!(matches!(
+            result.err().unwrap(),
+            ManifestError::InvalidGroup(key, TerariumBuilderError::TemplateNotFound(_)) if key == "broken"
+        ));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn parse_toml() {
+        let manifest = Manifest::from_toml_str(r#"
+            [[templates.subject]]
+            content = "Hello {{name}}"
+            languages = ["en"]
+
+            [[templates.subject]]
+            content = "Ahoj {{name}}"
+            languages = ["cs"]
+            name = "subject_cs"
+
+            [groups.email]
+            subject = "subject"
+        "#).unwrap();
+        assert_eq!(manifest.templates["subject"].len(), 2);
+        assert_eq!(manifest.templates["subject"][1].name, Some("subject_cs".to_owned()));
+        assert_eq!(manifest.groups["email"]["subject"], ManifestMember::new("subject".to_owned()));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn parse_json() {
+        let manifest = Manifest::from_json_str(r#"{
+            "templates": {"subject": [{"content": "Hello {{name}}", "languages": ["en"]}]},
+            "groups": {"email": {"subject": "subject", "body": "subject"}}
+        }"#).unwrap();
+        assert_eq!(manifest.templates["subject"][0].languages, vec!["en".to_owned()]);
+        assert_eq!(manifest.groups["email"]["subject"], ManifestMember::new("subject".to_owned()));
+        assert_eq!(manifest.groups["email"].keys().collect::<Vec<_>>(), ["subject", "body"]);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn parse_language_agnostic_content() {
+        let manifest = Manifest::from_json_str(r#"{"templates": {"logo": [{"content": "<svg/>"}]}, "groups": {}}"#).unwrap();
+        assert!(manifest.templates["logo"][0].is_wildcard());
+        let terarium = manifest.into_builder().unwrap().build().unwrap();
+        assert_eq!(terarium.render_template(&tera::Context::new(), "logo", "en", None).unwrap(), "<svg/>");
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn parse_yaml() {
+        let manifest = Manifest::from_yaml_str("
+templates:
+  subject:
+    - content: Hello {{name}}
+      languages: [en]
+groups:
+  email:
+    subject: subject
+    body: subject
+").unwrap();
+        assert_eq!(manifest.templates["subject"][0].content, "Hello {{name}}");
+        assert_eq!(manifest.groups["email"]["subject"], ManifestMember::new("subject".to_owned()));
+        assert_eq!(manifest.groups["email"].keys().collect::<Vec<_>>(), ["subject", "body"]);
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn parse_optional_member() {
+        let manifest = Manifest::from_toml_str(r#"
+            [[templates.subject]]
+            content = "Hello {{name}}"
+            languages = ["en"]
+
+            [[templates.preheader]]
+            content = "Ahoj"
+            languages = ["cs"]
+
+            [groups.email]
+            subject = "subject"
+            preheader = { template = "preheader", optional = true }
+        "#).unwrap();
+        assert_eq!(manifest.groups["email"]["preheader"], ManifestMember::new_optional("preheader".to_owned()));
+        let (_, group) = manifest.groups().unwrap().remove(0);
+        assert_eq!(group.iter().map(|(member_key, _)| member_key).collect::<Vec<_>>(), ["subject", "preheader"]);
+
+        let terarium = manifest.into_builder().unwrap().build().unwrap();
+        let mut ctx = Context::new();
+        ctx.insert("name", "john");
+        let group = terarium.render_group(&ctx, "email", "en", None).unwrap();
+        assert_eq!(group, std::collections::HashMap::from([("subject".to_owned(), "Hello john".to_owned())]));
+
+        let result = Manifest::from_toml_str(r#"
+            [groups.email]
+            subject = { template = "subject", optinal = true }
+        "#);
+        assert!(matches!(result.err().unwrap(), ManifestError::ParseError(_)));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn parse_error() {
+        let result = Manifest::from_toml_str("[templates");
+        assert!(matches!(result.err().unwrap(), ManifestError::ParseError(_)));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn missing_file() {
+        let result = Manifest::from_file("missing/catalog.toml");
+        assert!(matches!(result.err().unwrap(), ManifestError::Io(_)));
+    }
+
+    #[test]
+    fn invalid_template_syntax() {
+        let mut manifest = make_manifest();
+        manifest.templates.insert(
+            "broken".to_owned(),
+            vec![Content::new("Hello {{ name".to_owned(), vec!["en".to_owned()])],
+        );
+        let result = manifest.into_builder().unwrap().build();
+        assert!(matches!(result, Err(TerariumBuilderError::InvalidTemplateSyntax(key, _)) if key == "broken"));
+    }
+
+    fn make_manifest() -> Manifest {
+        Manifest {
+            templates: BTreeMap::from([(
+                "subject".to_owned(),
+                vec![
+                    Content::new("Hello {{name}}".to_owned(), vec!["en".to_owned()]),
+                    Content::new("Ahoj {{name}}".to_owned(), vec!["cs".to_owned()]),
+                ],
+            )]),
+            groups: BTreeMap::from([(
+                "email".to_owned(),
+                IndexMap::from([("subject".to_owned(), ManifestMember::new("subject".to_owned()))]),
+            )]),
+        }
+    }
+}
@@ -0,0 +1,30 @@
+/// Callback notified about fallbacks, missing translations and unsupported locales.
+pub(crate) type TranslationObserver = dyn Fn(&TranslationEvent) + Send + Sync;
+
+
+/// Kind of the translation event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TranslationEventKind {
+    /// Template was rendered, but not in the primary requested language.
+    Fallback,
+    /// Template has no content for any requested language.
+    Missing,
+    /// Template using the locale-aware filters was rendered in language without bundled locale data, so the values
+    /// were formatted by the `en` locale.
+    UnsupportedLocale,
+}
+
+
+/// Event passed to the translation observer set by the `TerariumBuilder::set_translation_observer()` method.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TranslationEvent<'a> {
+    /// Kind of the event.
+    pub kind: TranslationEventKind,
+    /// Key of the template.
+    pub template_key: &'a str,
+    /// Primary language requested by the caller (`None` if no language was requested).
+    pub requested_language: Option<&'a str>,
+    /// Language of the rendered content (`None` when the translation is missing or the language-agnostic content was
+    /// rendered).
+    pub resolved_language: Option<&'a str>,
+}
@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+use std::ops::Index;
+use std::vec::IntoIter;
+
+use crate::TerariumError;
+
+/// Rendered template with information about the resolved content.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RenderOutcome {
+    /// Rendered content.
+    pub content: String,
+    /// Language of the rendered content (`None` for the language-agnostic content).
+    pub language: Option<String>,
+    /// Name of the internal Tera template which was rendered.
+    pub template_name: String,
+    /// The content does not match the primary language of the preference (a fallback language or the
+    /// language-agnostic content was used).
+    pub fallback_used: bool,
+}
+
+
+/// Rendered members of the template group in order of the group definition.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RenderedGroup {
+    /// Member keys and rendered contents.
+    members: Vec<(String, String)>,
+}
+
+
+impl RenderedGroup {
+    /// Get rendered content of the member.
+    pub fn get(&self, member_key: &str) -> Option<&str> {
+        self.members.iter().find(|(key, _)| key == member_key).map(|(_, content)| content.as_str())
+    }
+
+    /// Iterate over member keys and rendered contents in order of the group definition.
+    pub fn iter(&self) -> impl Iterator<Item=(&str, &str)> {
+        self.members.iter().map(|(member_key, content)| (member_key.as_str(), content.as_str()))
+    }
+
+    /// Get number of rendered members.
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    /// Return `true` if no member was rendered.
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// Add rendered content of the member after the already rendered ones.
+    pub(crate) fn push(&mut self, member_key: String, content: String) {
+        self.members.push((member_key, content));
+    }
+}
+
+
+impl Index<&str> for RenderedGroup {
+    type Output = str;
+
+    /// Get rendered content of the member.
+    /// Panics if the member was not rendered.
+    fn index(&self, member_key: &str) -> &Self::Output {
+        self.get(member_key).unwrap_or_else(|| panic!("Member {} was not rendered", member_key))
+    }
+}
+
+
+impl IntoIterator for RenderedGroup {
+    type Item = (String, String);
+    type IntoIter = IntoIter<(String, String)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.members.into_iter()
+    }
+}
+
+
+impl FromIterator<(String, String)> for RenderedGroup {
+    fn from_iter<T: IntoIterator<Item=(String, String)>>(iter: T) -> Self {
+        Self { members: iter.into_iter().collect() }
+    }
+}
+
+
+impl From<RenderedGroup> for HashMap<String, String> {
+    fn from(value: RenderedGroup) -> Self {
+        value.into_iter().collect()
+    }
+}
+
+
+/// Result of the template group render for one item of the batch.
+pub type GroupRenderResult = Result<RenderedGroup, TerariumError>;
+
+
+/// Result of the template group render which does not stop on the first failing member.
+/// Both successful and failed members are in order of the group definition.
+#[derive(Debug, Default)]
+pub struct GroupRenderReport {
+    /// Rendered contents of the successful members.
+    pub rendered: RenderedGroup,
+    /// Member keys and errors of the failed members.
+    pub failed: Vec<(String, TerariumError)>,
+}
+
+
+impl GroupRenderReport {
+    /// Return `true` if all members were rendered.
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    /// Get error of the failed member.
+    pub fn error(&self, member_key: &str) -> Option<&TerariumError> {
+        self.failed.iter().find(|(key, _)| key == member_key).map(|(_, err)| err)
+    }
+}
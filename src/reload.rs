@@ -0,0 +1,296 @@
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock, Weak};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use thiserror::Error;
+
+use crate::{DirectoryLoader, TemplateSource, Terarium, TerariumBuilder, TerariumBuilderError};
+
+/// Function loading fresh builder from template sources.
+type BuilderLoader = dyn Fn() -> Result<TerariumBuilder, ReloadError> + Send + Sync;
+
+/// Callback notified about failed reloads.
+type ErrorCallback = dyn Fn(&ReloadError) + Send + Sync;
+
+/// Reloadable handle around the `Terarium` instance.
+///
+/// The handle holds the live `Terarium` instance and the function loading templates from their sources. When the
+/// instance is reloaded, new instance is built by the `TerariumBuilder::build()` and atomically swapped with the live
+/// one. Renders use snapshot returned by the `current()` method, so already running renders finish on the old
+/// instance. When the reload fails, the last good instance keeps serving and the error is passed to the error callback.
+pub struct ReloadableTerarium {
+    /// Live instance.
+    current: RwLock<Arc<Terarium>>,
+    /// Loader of the template sources.
+    loader: Box<BuilderLoader>,
+    /// Callback called when reload fails.
+    on_error: Option<Box<ErrorCallback>>,
+    /// Files and directories of the template sources watched by the `watch()` method.
+    watched_paths: Vec<PathBuf>,
+}
+
+
+impl ReloadableTerarium {
+    /// Create new handle. The `loader` is called immediately to build the initial instance and then on every reload.
+    pub fn new<F, E>(loader: F) -> Result<Self, ReloadError>
+        where
+            F: Fn() -> Result<TerariumBuilder, E> + Send + Sync + 'static,
+            E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        let loader: Box<BuilderLoader> = Box::new(move || loader().map_err(|err| ReloadError::LoadingFailed(err.into())));
+        let instance = loader()?.build()?;
+        Ok(Self {
+            current: RwLock::new(Arc::new(instance)),
+            loader,
+            on_error: None,
+            watched_paths: vec![],
+        })
+    }
+
+    /// Create new handle loading templates from the directory by the `DirectoryLoader`.
+    pub fn from_directory(root: impl Into<PathBuf>) -> Result<Self, ReloadError> {
+        let root = root.into();
+        let loader = DirectoryLoader::new(root.clone());
+        let mut instance = Self::new(move || loader.load())?;
+        instance.watched_paths = vec![root];
+        Ok(instance)
+    }
+
+    /// Create new handle loading templates and groups from the template source.
+    pub fn from_source(source: impl TemplateSource + Send + Sync + 'static) -> Result<Self, ReloadError> {
+        Self::new(move || {
+            let mut builder = TerariumBuilder::default();
+            builder.add_source(&source)?;
+            Ok::<_, TerariumBuilderError>(builder)
+        })
+    }
+
+    /// Create new handle loading templates from the manifest file.
+    #[cfg(feature = "manifest")]
+    pub fn from_manifest(path: impl Into<PathBuf>) -> Result<Self, ReloadError> {
+        let path = path.into();
+        let manifest_path = path.clone();
+        let mut instance = Self::new(move || crate::Manifest::from_file(&manifest_path)?.into_builder())?;
+        instance.watched_paths = vec![path];
+        Ok(instance)
+    }
+
+    /// Set callback called when reload fails.
+    pub fn with_error_callback(mut self, callback: impl Fn(&ReloadError) + Send + Sync + 'static) -> Self {
+        self.on_error = Some(Box::new(callback));
+        self
+    }
+
+    /// Get snapshot of the live instance.
+    pub fn current(&self) -> Arc<Terarium> {
+        self.current.read().unwrap_or_else(|err| err.into_inner()).clone()
+    }
+
+    /// Load templates from sources, build new instance and swap it with the live one.
+    /// If loading or building fails, the live instance is kept, the error callback is called and the error is returned.
+    pub fn reload(&self) -> Result<(), ReloadError> {
+        match (self.loader)().and_then(|builder| Ok(builder.build()?)) {
+            Ok(instance) => {
+                *self.current.write().unwrap_or_else(|err| err.into_inner()) = Arc::new(instance);
+                Ok(())
+            }
+            Err(err) => {
+                if let Some(callback) = &self.on_error {
+                    callback(&err);
+                }
+                Err(err)
+            }
+        }
+    }
+
+    /// Watch the directory or the manifest file the handle was created from and reload the instance when it changes.
+    ///
+    /// Handles created by the `new()` or `from_source()` methods do not know their files, use the `watch_paths()`
+    /// method for them. See `watch_paths()` for details.
+    pub fn watch(self: &Arc<Self>, interval: Duration) -> ReloadWatcher {
+        self.watch_paths(self.watched_paths.clone(), interval)
+    }
+
+    /// Watch files and directories in `paths` and reload the instance when any of them changes.
+    ///
+    /// The paths are checked by a background thread every `interval`. Watching stops when returned `ReloadWatcher` or
+    /// the last reference to the handle is dropped. Reload errors are reported by the error callback only.
+    pub fn watch_paths(self: &Arc<Self>, paths: Vec<PathBuf>, interval: Duration) -> ReloadWatcher {
+        let stopped = Arc::new(AtomicBool::new(false));
+        let handle: Weak<Self> = Arc::downgrade(self);
+        let thread_stopped = stopped.clone();
+        let mut last_fingerprint = fingerprint(&paths);
+
+        thread::spawn(move || {
+            while !thread_stopped.load(Ordering::Relaxed) {
+                thread::sleep(interval);
+                let current_fingerprint = fingerprint(&paths);
+                if current_fingerprint == last_fingerprint {
+                    continue;
+                }
+                last_fingerprint = current_fingerprint;
+                match handle.upgrade() {
+                    Some(instance) => {
+                        let _ = instance.reload();
+                    }
+                    None => break,
+                }
+            }
+        });
+
+        ReloadWatcher { stopped }
+    }
+}
+
+
+/// Guard of the background thread started by the `ReloadableTerarium::watch()` method.
+/// The watching stops when the guard is dropped.
+pub struct ReloadWatcher {
+    /// Flag signalizing the thread to stop.
+    stopped: Arc<AtomicBool>,
+}
+
+
+impl Drop for ReloadWatcher {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::Relaxed);
+    }
+}
+
+
+/// Errors returned by `ReloadableTerarium` operations.
+#[derive(Debug, Error)]
+pub enum ReloadError {
+    /// Template sources cannot be loaded.
+    #[error("Unable to load templates")]
+    LoadingFailed(#[source] Box<dyn Error + Send + Sync>),
+    /// Loaded templates cannot be built.
+    #[error("Unable to build templates")]
+    BuildingFailed(#[source] TerariumBuilderError),
+}
+
+
+impl From<TerariumBuilderError> for ReloadError {
+    fn from(value: TerariumBuilderError) -> Self {
+        Self::BuildingFailed(value)
+    }
+}
+
+
+/// Collect paths, modification times and sizes of all files in `paths`.
+fn fingerprint(paths: &[PathBuf]) -> Vec<(PathBuf, Option<SystemTime>, u64)> {
+    let mut result = Vec::new();
+    paths.iter().for_each(|path| collect_fingerprint(path, &mut result));
+    result
+}
+
+
+/// Recursively collect fingerprint of the `path`.
+fn collect_fingerprint(path: &Path, result: &mut Vec<(PathBuf, Option<SystemTime>, u64)>) {
+    let Ok(metadata) = fs::metadata(path) else {
+        return;
+    };
+    if metadata.is_dir() {
+        let Ok(entries) = fs::read_dir(path) else {
+            return;
+        };
+        let mut entries = entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()).collect::<Vec<_>>();
+        entries.sort();
+        entries.iter().for_each(|entry| collect_fingerprint(entry, result));
+    } else {
+        result.push((path.to_owned(), metadata.modified().ok(), metadata.len()));
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+    use std::time::Instant;
+
+    use tempfile::TempDir;
+    use tera::Context;
+
+    use super::*;
+
+    #[test]
+    fn reload_templates() {
+        let dir = make_directory("Hello");
+        let reloadable = ReloadableTerarium::from_directory(dir.path()).unwrap();
+        let old_snapshot = reloadable.current();
+
+        fs::write(dir.path().join("subject.en.txt"), "Hello again").unwrap();
+        reloadable.reload().unwrap();
+
+        assert_eq!(render(&reloadable.current()), "Hello again");
+        assert_eq!(render(&old_snapshot), "Hello");
+    }
+
+    #[test]
+    fn keep_last_good_instance() {
+        let dir = make_directory("Hello");
+        let errors = Arc::new(Mutex::new(0));
+        let callback_errors = errors.clone();
+        let reloadable = ReloadableTerarium::from_directory(dir.path())
+            .unwrap()
+            .with_error_callback(move |_| *callback_errors.lock().unwrap() += 1);
+
+        fs::write(dir.path().join("subject.en.txt"), "Hello {{").unwrap();
+        let result = reloadable.reload();
+
+        let err = result.unwrap_err();
+        assert!(matches!(err, ReloadError::BuildingFailed(_)));
+        assert!(matches!(err.source().unwrap().downcast_ref(), Some(TerariumBuilderError::InvalidTemplateSyntax(..))));
+        assert_eq!(*errors.lock().unwrap(), 1);
+        assert_eq!(render(&reloadable.current()), "Hello");
+    }
+
+    #[test]
+    fn initial_load_failure() {
+        let result = ReloadableTerarium::new(|| Err("no templates"));
+        assert!(matches!(result.err().unwrap(), ReloadError::LoadingFailed(_)));
+    }
+
+    #[test]
+    fn watch_directory() {
+        let dir = make_directory("Hello");
+        let reloadable = Arc::new(ReloadableTerarium::from_directory(dir.path()).unwrap());
+        let _watcher = reloadable.watch(Duration::from_millis(10));
+
+        fs::write(dir.path().join("subject.en.txt"), "Hello from watcher").unwrap();
+        assert_eq!(wait_for_reload(&reloadable, "Hello"), "Hello from watcher");
+    }
+
+    #[test]
+    fn watch_custom_source_paths() {
+        let dir = make_directory("Hello");
+        let loader = DirectoryLoader::new(dir.path());
+        let reloadable = Arc::new(ReloadableTerarium::new(move || loader.load()).unwrap());
+        let _watcher = reloadable.watch_paths(vec![dir.path().to_owned()], Duration::from_millis(10));
+
+        fs::write(dir.path().join("subject.en.txt"), "Hello from watcher").unwrap();
+        assert_eq!(wait_for_reload(&reloadable, "Hello"), "Hello from watcher");
+    }
+
+    fn wait_for_reload(reloadable: &ReloadableTerarium, old_content: &str) -> String {
+        let started = Instant::now();
+        while render(&reloadable.current()) == old_content && started.elapsed() < Duration::from_secs(5) {
+            thread::sleep(Duration::from_millis(10));
+        }
+        render(&reloadable.current())
+    }
+
+    fn make_directory(content: &str) -> TempDir {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("subject.en.txt"), content).unwrap();
+        dir
+    }
+
+    fn render(terarium: &Terarium) -> String {
+        terarium.render_template(&Context::new(), "subject", "en", None).unwrap()
+    }
+}
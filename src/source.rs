@@ -0,0 +1,126 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+
+use crate::{Content, TemplateGroup};
+
+/// Error returned by template sources.
+pub type SourceError = Box<dyn Error + Send + Sync>;
+
+/// Result returned by template sources.
+pub type SourceResult<T> = Result<T, SourceError>;
+
+/// Source of templates and groups consumed by the `TerariumBuilder::add_source()` method.
+///
+/// Implement this trait to load templates from custom storage (database, key-value store, configuration, ...).
+/// The `DirectoryLoader` and `MemorySource` can be used as reference implementations.
+pub trait TemplateSource {
+    /// Get all templates provided by the source as pairs of template key and its contents.
+    fn templates(&self) -> SourceResult<Vec<(String, Vec<Content>)>>;
+
+    /// Get all groups provided by the source as pairs of group key and group definition.
+    fn groups(&self) -> SourceResult<Vec<(String, TemplateGroup)>>;
+}
+
+
+/// Template source holding templates and groups in memory.
+#[derive(Clone, Debug, Default)]
+pub struct MemorySource {
+    /// Contents by template key.
+    templates: BTreeMap<String, Vec<Content>>,
+    /// Groups by group key.
+    groups: BTreeMap<String, TemplateGroup>,
+}
+
+
+impl MemorySource {
+    /// Add template contents.
+    /// If template exists, it will be replaced.
+    pub fn add_template(&mut self, key: String, contents: Vec<Content>) {
+        self.templates.insert(key, contents);
+    }
+
+    /// Add group members.
+    /// If group exists, it will be replaced.
+    pub fn add_group(&mut self, key: String, group: impl Into<TemplateGroup>) {
+        self.groups.insert(key, group.into());
+    }
+}
+
+
+impl TemplateSource for MemorySource {
+    fn templates(&self) -> SourceResult<Vec<(String, Vec<Content>)>> {
+        Ok(self.templates.iter().map(|(key, contents)| (key.clone(), contents.clone())).collect())
+    }
+
+    fn groups(&self) -> SourceResult<Vec<(String, TemplateGroup)>> {
+        Ok(self.groups.iter().map(|(key, group)| (key.clone(), group.clone())).collect())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use tera::Context;
+
+    use crate::{TemplateError, TemplateGroupBuilder, TerariumBuilder, TerariumBuilderError};
+
+    use super::*;
+
+    #[test]
+    fn add_memory_source() {
+        let mut builder = TerariumBuilder::default();
+        builder.add_source(&make_source()).unwrap();
+        let terarium = builder.build().unwrap();
+
+        let mut ctx = Context::new();
+        ctx.insert("name", "john");
+        assert_eq!(terarium.render_template(&ctx, "subject", "cs", None).unwrap(), "Ahoj john");
+        assert_eq!(terarium.render_group(&ctx, "email", "en", None).unwrap()["subject"], "Hello john");
+    }
+
+    #[test]
+    fn invalid_template() {
+        let mut source = make_source();
+        source.add_template("broken".to_owned(), vec![
+            Content::new("a".to_owned(), vec!["en".to_owned()]),
+            Content::new("b".to_owned(), vec!["en".to_owned()]),
+        ]);
+        let result = TerariumBuilder::default().add_source(&source);
+        assert!(matches!(
+            result.unwrap_err(),
+            TerariumBuilderError::InvalidTemplate(key, TemplateError::DuplicatedContentLanguages(_)) if key == "broken"
+        ));
+    }
+
+    #[test]
+    fn failing_source() {
+        struct FailingSource;
+
+        impl TemplateSource for FailingSource {
+            fn templates(&self) -> SourceResult<Vec<(String, Vec<Content>)>> {
+                Err("storage is not available".into())
+            }
+
+            fn groups(&self) -> SourceResult<Vec<(String, TemplateGroup)>> {
+                Ok(vec![])
+            }
+        }
+
+        let err = TerariumBuilder::default().add_source(&FailingSource).unwrap_err();
+        assert!(matches!(err, TerariumBuilderError::SourceError(_)));
+        assert_eq!(err.source().unwrap().to_string(), "storage is not available");
+    }
+
+    fn make_source() -> MemorySource {
+        let mut source = MemorySource::default();
+        source.add_template("subject".to_owned(), vec![
+            Content::new("Hello {{name}}".to_owned(), vec!["en".to_owned()]),
+            Content::new("Ahoj {{name}}".to_owned(), vec!["cs".to_owned()]),
+        ]);
+        source.add_group(
+            "email".to_owned(),
+            TemplateGroupBuilder::default().add_member("subject".to_owned(), "subject".to_owned()).build(),
+        );
+        source
+    }
+}
@@ -1,5 +1,6 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
+use tera::Context;
 use thiserror::Error;
 
 
@@ -32,7 +33,8 @@ impl Template {
     }
 
     /// Add new content into template.
-    /// Return handle of the content.
+    /// Content without languages is language-agnostic and it is used when no language-specific content matches.
+    /// More language-agnostic contents are reported by the `TerariumBuilder::build()` method.
     pub fn add_content(&mut self, content: Content) -> Result<(), TemplateError> {
         let mut languages_to_add = Vec::<String>::new();
         let mut names_to_add = Vec::<String>::new();
@@ -57,9 +59,8 @@ impl Template {
     }
 
     /// Collect template content settings as Vec
-    /// When content has no language, this content is dropped
     pub fn collect_contents(self) -> Vec<Content> {
-        self.contents.into_iter().filter(|c| c.languages.len() > 0).collect()
+        self.contents
     }
 }
 
@@ -79,13 +80,18 @@ pub enum TemplateError {
 
 /// Represent content of template
 #[derive(Clone, Default, Debug)]
+#[cfg_attr(feature = "manifest", derive(serde::Deserialize))]
+#[cfg_attr(feature = "manifest", serde(deny_unknown_fields))]
 pub struct Content {
     /// Template content.
     pub content: String,
     /// Assigned languages.
+    /// When no language is assigned, the content is language-agnostic (used when no other content matches).
+    #[cfg_attr(feature = "manifest", serde(default))]
     pub languages: Vec<String>,
     /// Name of the content.
     /// The name can be used for referenced for example by {% include %} statement.
+    #[cfg_attr(feature = "manifest", serde(default))]
     pub name: Option<String>,
 }
 
@@ -100,6 +106,15 @@ impl Content {
         }
     }
 
+    /// Create new language-agnostic instance.
+    /// The content is used when no language-specific content of the template matches requested languages.
+    pub fn new_wildcard(content: String) -> Self {
+        Self {
+            content,
+            ..Self::default()
+        }
+    }
+
     /// Create new instance with name set
     pub fn new_named(content: String, languages: Vec<String>, name: String) -> Self {
         Self {
@@ -108,6 +123,143 @@ impl Content {
             name: Some(name),
         }
     }
+
+    /// Return `true` if the content is language-agnostic (no language is assigned).
+    pub fn is_wildcard(&self) -> bool {
+        self.languages.is_empty()
+    }
+}
+
+
+/// Definition of the template group.
+/// Each member of the group is identified by its member key and refers to a template. Members keep the order in which
+/// they were added.
+///
+/// The group can define context overlays merged on top of the caller's context. The merge order is: the caller's
+/// context, the group context, the member context and the reserved language variables (later layers overwrite
+/// variables of the earlier ones).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TemplateGroup {
+    /// Member keys and members in order of insertion.
+    members: Vec<(String, GroupMember)>,
+    /// Context overlay applied to all members.
+    context: Option<Context>,
+    /// Context overlays by member key.
+    member_contexts: HashMap<String, Context>,
+}
+
+
+// Contexts hold JSON values only (numbers cannot be NaN), so their equality is an equivalence relation.
+impl Eq for TemplateGroup {}
+
+
+impl TemplateGroup {
+    /// Add member at the end of the group.
+    /// If member with the same key exists, it is replaced and keeps its position.
+    pub fn add_member(&mut self, member_key: String, member: GroupMember) {
+        match self.members.iter_mut().find(|(key, _)| *key == member_key) {
+            Some((_, existing)) => *existing = member,
+            None => self.members.push((member_key, member)),
+        }
+    }
+
+    /// Get member by its key.
+    pub fn get(&self, member_key: &str) -> Option<&GroupMember> {
+        self.members.iter().find(|(key, _)| key == member_key).map(|(_, member)| member)
+    }
+
+    /// Set context overlay applied to all members.
+    pub fn set_context(&mut self, context: Context) {
+        self.context = Some(context);
+    }
+
+    /// Get context overlay applied to all members.
+    pub fn context(&self) -> Option<&Context> {
+        self.context.as_ref()
+    }
+
+    /// Set context overlay applied to the member identified by the `member_key`.
+    /// The member has to be in the group, otherwise the group is rejected by the `TerariumBuilder::add_group()` method.
+    pub fn set_member_context(&mut self, member_key: String, context: Context) {
+        self.member_contexts.insert(member_key, context);
+    }
+
+    /// Get context overlay of the member identified by the `member_key`.
+    pub fn member_context(&self, member_key: &str) -> Option<&Context> {
+        self.member_contexts.get(member_key)
+    }
+
+    /// Iterate over member keys of the member context overlays.
+    pub(crate) fn member_context_keys(&self) -> impl Iterator<Item=&String> {
+        self.member_contexts.keys()
+    }
+
+    /// Iterate over member keys and members in order of insertion.
+    pub fn iter(&self) -> impl Iterator<Item=(&String, &GroupMember)> {
+        self.members.iter().map(|(member_key, member)| (member_key, member))
+    }
+
+    /// Iterate over member keys and members in parallel by the `rayon` thread pool.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn par_iter(&self) -> impl rayon::iter::IndexedParallelIterator<Item=(&String, &GroupMember)> {
+        use rayon::prelude::*;
+
+        self.members.par_iter().map(|(member_key, member)| (member_key, member))
+    }
+
+    /// Get number of members.
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    /// Return `true` if the group has no member.
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+}
+
+
+/// Members are added in the iteration order of the map, which is arbitrary. Collect the group from pairs of member key
+/// and template key (or use the `TemplateGroupBuilder`) when the order of members matters.
+impl From<HashMap<String, String>> for TemplateGroup {
+    fn from(value: HashMap<String, String>) -> Self {
+        value.into_iter().collect()
+    }
+}
+
+
+impl FromIterator<(String, String)> for TemplateGroup {
+    fn from_iter<T: IntoIterator<Item=(String, String)>>(iter: T) -> Self {
+        let mut group = Self::default();
+        for (member_key, template_key) in iter {
+            group.add_member(member_key, GroupMember::new(template_key));
+        }
+        group
+    }
+}
+
+
+/// Member of the template group.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GroupMember {
+    /// Key of the rendered template.
+    pub template_key: String,
+    /// Optional member is left out of the group render result when its template has no content for requested
+    /// languages (instead of failing the whole group).
+    pub optional: bool,
+}
+
+
+impl GroupMember {
+    /// Create new mandatory member.
+    pub fn new(template_key: String) -> Self {
+        Self { template_key, optional: false }
+    }
+
+    /// Create new optional member.
+    pub fn new_optional(template_key: String) -> Self {
+        Self { template_key, optional: true }
+    }
 }
 
 
@@ -163,7 +315,7 @@ mod tests {
             template.add_content(Content::new("foo foo".to_string(), vec![])).unwrap();
 
             let contents = template.collect_contents();
-            assert_eq!(contents.len(), 2);
+            assert_eq!(contents.len(), 3);
             let mut languages_by_content = HashMap::<String, Vec<String>>::new();
 
             contents.iter().for_each(|c| {
@@ -172,10 +324,29 @@ mod tests {
 
             assert_eq!(languages_by_content["foo bar"], vec!["1".to_owned(), "2".to_owned()]);
             assert_eq!(languages_by_content["bar bar"], vec!["3".to_owned()]);
+            assert!(contents.iter().any(|c| c.content == "foo foo" && c.is_wildcard()));
         }
 
         fn empty_template() -> Template {
             Template::default()
         }
     }
+
+    mod template_group {
+        use crate::{GroupMember, TemplateGroup};
+
+        #[test]
+        fn keep_insertion_order() {
+            let mut group = TemplateGroup::default();
+            group.add_member("subject".to_owned(), GroupMember::new("subject".to_owned()));
+            group.add_member("text".to_owned(), GroupMember::new("text".to_owned()));
+            group.add_member("html".to_owned(), GroupMember::new("html".to_owned()));
+            group.add_member("text".to_owned(), GroupMember::new_optional("plain".to_owned()));
+
+            let member_keys = group.iter().map(|(member_key, _)| member_key.as_str()).collect::<Vec<_>>();
+            assert_eq!(member_keys, ["subject", "text", "html"]);
+            assert_eq!(group.get("text"), Some(&GroupMember::new_optional("plain".to_owned())));
+            assert_eq!(group.len(), 3);
+        }
+    }
 }
@@ -1,12 +1,20 @@
-use std::borrow::Borrow;
+use std::borrow::Cow;
 use std::collections::HashMap;
-use std::hash::Hash;
+use std::io::{Error as IoError, Write};
+use std::sync::Arc;
 
 use tera::{Context, Error as TeraError};
 use tera::Tera;
 use thiserror::Error;
 
-use crate::Template;
+use crate::{
+    GroupMember, GroupRenderReport, GroupRenderResult, LanguagePreference, MemberField, RenderOutcome, RenderedGroup,
+    SourceError, Template, TemplateError, TemplateGroup, TemplateSource, TerariumGroup, TranslationEvent,
+    TranslationEventKind,
+};
+use crate::language::{language_tag_chain, normalize_language_tag, parse_accept_language, text_direction};
+use crate::locale::{is_supported_language, localize_template, register_filters};
+use crate::observer::TranslationObserver;
 
 /// Wrapper over the `Tera` templating engine with capability of template bulk rendering.
 /// Each template can exists in more than one version (support for multi-language templates).
@@ -17,13 +25,26 @@ pub struct Terarium {
     tera: Tera,
     /// Template by template key lookup.
     template_map: HashMap<String, HashMap<String, String>>,
+    /// Language-agnostic content by template key lookup.
+    wildcards: HashMap<String, String>,
     /// Group by group key lookup.
-    groups: HashMap<String, HashMap<String, String>>,
+    groups: HashMap<String, TemplateGroup>,
+    /// Fallback languages by template key.
+    template_fallbacks: HashMap<String, Vec<String>>,
+    /// Fallback languages by group key.
+    group_fallbacks: HashMap<String, Vec<String>>,
+    /// Language keys are normalized BCP 47 language tags.
+    language_tags: bool,
+    /// Observer notified about fallbacks and missing translations.
+    observer: Option<Arc<TranslationObserver>>,
+    /// Template key by name of the Tera template using the locale-aware filters.
+    locale_templates: HashMap<String, String>,
 }
 
 impl Terarium {
     /// Render single template identified by its key.
     /// The `Tera` context is accepted for rendering.
+    #[allow(clippy::multiple_bound_locations)]
     pub fn render_template<K: ?Sized, LK: ?Sized>(
         &self,
         context: &Context,
@@ -32,24 +53,58 @@ impl Terarium {
         fallback_language: Option<&LK>,
     ) -> Result<String, TerariumError>
         where
-            String: Borrow<K>,
-            String: Borrow<LK>,
-            K: Hash + Eq,
-            LK: Hash + Eq,
+            K: AsRef<str>,
+            LK: AsRef<str>,
     {
-        let template = self
-            .template_map.get(template_key).ok_or_else(|| TerariumError::TemplateNotFound)?;
-        let content_key = template
-            .get(language)
-            .or_else(|| {
-                fallback_language.map(|k| template.get(k)).flatten()
-            })
-            .ok_or_else(|| TerariumError::LanguageNotFound)?;
-        Ok(self.tera.render(content_key.as_str(), context)?)
+        let preference = make_preference(language.as_ref(), fallback_language.map(|k| k.as_ref()));
+        self.render_template_with_preference(context, template_key.as_ref(), &preference)
+    }
+
+    /// Render single template identified by its key.
+    /// Languages of the `preference` are tried in order and then the fallback languages configured for the template.
+    pub fn render_template_with_preference(
+        &self,
+        context: &Context,
+        template_key: &str,
+        preference: &LanguagePreference,
+    ) -> Result<String, TerariumError> {
+        Ok(self.render_template_detailed(context, template_key, preference)?.content)
+    }
+
+    /// Render single template like `render_template_with_preference()` and return the rendered content with the
+    /// resolved language, the name of the internal Tera template and the flag whether the fallback was used.
+    pub fn render_template_detailed(
+        &self,
+        context: &Context,
+        template_key: &str,
+        preference: &LanguagePreference,
+    ) -> Result<RenderOutcome, TerariumError> {
+        let resolved = self.resolve_content(template_key, preference, &[])?;
+        self.render_resolved(Cow::Borrowed(context), resolved, preference)
+    }
+
+    /// Render single template into the `writer` without allocating the whole output.
+    /// Languages are resolved like in the `render_template()` method.
+    pub fn render_template_to<K, LK>(
+        &self,
+        context: &Context,
+        template_key: &K,
+        language: &LK,
+        fallback_language: Option<&LK>,
+        writer: impl Write,
+    ) -> Result<(), TerariumError>
+        where
+            K: AsRef<str> + ?Sized,
+            LK: AsRef<str> + ?Sized,
+    {
+        let preference = make_preference(language.as_ref(), fallback_language.map(|k| k.as_ref()));
+        let resolved = self.resolve_content(template_key.as_ref(), &preference, &[])?;
+        self.render_content_to(Cow::Borrowed(context), resolved, &preference, writer)
     }
 
     /// Render template group.
     /// Result is HashMap where keys are member names and values are rendered templates.
+    #[allow(clippy::multiple_bound_locations)]
     pub fn render_group<K: ?Sized, LK: ?Sized>(
         &self,
         context: &Context,
@@ -58,21 +113,806 @@ impl Terarium {
         fallback_language: Option<&LK>,
     ) -> Result<HashMap<String, String>, TerariumError>
         where
-            String: Borrow<K>,
-            String: Borrow<LK>,
-            K: Hash + Eq,
-            LK: Hash + Eq,
+            K: AsRef<str>,
+            LK: AsRef<str>,
     {
+        let preference = make_preference(language.as_ref(), fallback_language.map(|k| k.as_ref()));
+        self.render_group_with_preference(context, group_key.as_ref(), &preference)
+    }
+
+    /// Render template group.
+    /// Languages of the `preference` are tried in order, then the fallback languages configured for the group and then
+    /// the fallback languages configured for the member template.
+    pub fn render_group_with_preference(
+        &self,
+        context: &Context,
+        group_key: &str,
+        preference: &LanguagePreference,
+    ) -> Result<HashMap<String, String>, TerariumError> {
+        Ok(self
+            .render_group_detailed(context, group_key, preference)?
+            .into_iter()
+            .map(|(member_key, outcome)| (member_key, outcome.content))
+            .collect())
+    }
+
+    /// Render template group like `render_group_with_preference()`, but keep members in order of the group definition.
+    /// Contents of all members are resolved before the first member is rendered.
+    pub fn render_group_ordered(
+        &self,
+        context: &Context,
+        group_key: &str,
+        preference: &LanguagePreference,
+    ) -> Result<RenderedGroup, TerariumError> {
         let group = self.groups.get(group_key).ok_or_else(|| TerariumError::GroupNotFound)?;
-        let mut result = HashMap::<String, String>::new();
+        let group_fallbacks = self.group_fallbacks.get(group_key).map(|f| f.as_slice()).unwrap_or_default();
+        let members = self.resolve_group(group, preference, group_fallbacks)?;
+        self.render_resolved_group(context, group, &members, preference)
+    }
 
-        for (member_key, template_key) in group.iter() {
-            let content = self.render_template(context, template_key, language, fallback_language)?;
-            result.insert(member_key.clone(), content);
+    /// Render template group into the struct implementing the `TerariumGroup` trait.
+    /// Languages are resolved like in the `render_group_with_preference()` method.
+    pub fn render_group_as<T: TerariumGroup>(
+        &self,
+        context: &Context,
+        group_key: &str,
+        preference: &LanguagePreference,
+    ) -> Result<T, TerariumError> {
+        T::from_rendered(self.render_group_with_preference(context, group_key, preference)?)
+    }
+
+    /// Render template group into writers created by the `writer_factory` for each member key.
+    ///
+    /// Languages are resolved like in the `render_group()` method. Contents of all members are resolved before the
+    /// first writer is created, so missing templates and languages are reported without partial output.
+    pub fn render_group_to<K, LK, W, F>(
+        &self,
+        context: &Context,
+        group_key: &K,
+        language: &LK,
+        fallback_language: Option<&LK>,
+        mut writer_factory: F,
+    ) -> Result<(), TerariumError>
+        where
+            K: AsRef<str> + ?Sized,
+            LK: AsRef<str> + ?Sized,
+            W: Write,
+            F: FnMut(&str) -> Result<W, IoError>,
+    {
+        let preference = make_preference(language.as_ref(), fallback_language.map(|k| k.as_ref()));
+        let group = self.groups.get(group_key.as_ref()).ok_or_else(|| TerariumError::GroupNotFound)?;
+        let group_fallbacks = self.group_fallbacks.get(group_key.as_ref()).map(|f| f.as_slice()).unwrap_or_default();
+        let members = self.resolve_group(group, &preference, group_fallbacks)?;
+
+        for (member_key, resolved) in members {
+            let writer = writer_factory(member_key).map_err(TerariumError::WriterFailed)?;
+            let context = make_member_context(context, group, member_key);
+            self.render_content_to(context, resolved, &preference, writer)?;
+        }
+        Ok(())
+    }
+
+    /// Render template group like `render_group_with_preference()` and return the `RenderOutcome` of each member.
+    pub fn render_group_detailed(
+        &self,
+        context: &Context,
+        group_key: &str,
+        preference: &LanguagePreference,
+    ) -> Result<HashMap<String, RenderOutcome>, TerariumError> {
+        let group = self.groups.get(group_key).ok_or_else(|| TerariumError::GroupNotFound)?;
+        let group_fallbacks = self.group_fallbacks.get(group_key).map(|f| f.as_slice()).unwrap_or_default();
+        let mut result = HashMap::<String, RenderOutcome>::new();
+
+        for (member_key, member) in group.iter() {
+            if let Some(resolved) = self.resolve_member(member, preference, group_fallbacks)? {
+                let context = make_member_context(context, group, member_key);
+                result.insert(member_key.clone(), self.render_resolved(context, resolved, preference)?);
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Render template group like `render_group_with_preference()`, but do not stop on the first failing member.
+    /// The report contains rendered contents of successful members and errors of the failed ones.
+    pub fn render_group_partial(
+        &self,
+        context: &Context,
+        group_key: &str,
+        preference: &LanguagePreference,
+    ) -> Result<GroupRenderReport, TerariumError> {
+        let group = self.groups.get(group_key).ok_or_else(|| TerariumError::GroupNotFound)?;
+        let group_fallbacks = self.group_fallbacks.get(group_key).map(|f| f.as_slice()).unwrap_or_default();
+        let mut report = GroupRenderReport::default();
+
+        for (member_key, member) in group.iter() {
+            let context = make_member_context(context, group, member_key);
+            let result = self.resolve_member(member, preference, group_fallbacks).and_then(|resolved| {
+                resolved.map(|resolved| self.render_resolved(context, resolved, preference)).transpose()
+            });
+            match result {
+                Ok(Some(outcome)) => {
+                    report.rendered.push(member_key.clone(), outcome.content);
+                }
+                Ok(None) => {}
+                Err(err) => {
+                    report.failed.push((member_key.clone(), err));
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Render template group like `render_group_with_preference()` with members rendered in parallel by the `rayon`
+    /// thread pool. When more members fail, the same error as by `render_group_with_preference()` is returned (the
+    /// first resolution error in order of members, otherwise the first render error).
+    #[cfg(feature = "rayon")]
+    pub fn render_group_par(
+        &self,
+        context: &Context,
+        group_key: &str,
+        preference: &LanguagePreference,
+    ) -> Result<HashMap<String, String>, TerariumError> {
+        use rayon::prelude::*;
+
+        let group = self.groups.get(group_key).ok_or_else(|| TerariumError::GroupNotFound)?;
+        let group_fallbacks = self.group_fallbacks.get(group_key).map(|f| f.as_slice()).unwrap_or_default();
+
+        // Results are collected in order of members, so the returned error does not depend on the thread scheduling.
+        let results = group
+            .par_iter()
+            .map(|(member_key, member)| {
+                let resolved = self.resolve_member(member, preference, group_fallbacks)?;
+                Ok(resolved.map(|resolved| {
+                    let context = make_member_context(context, group, member_key);
+                    let outcome = self.render_resolved(context, resolved, preference)?;
+                    Ok((member_key.clone(), outcome.content))
+                }))
+            })
+            .collect::<Vec<Result<Option<Result<_, TerariumError>>, TerariumError>>>();
+        results.into_iter().collect::<Result<Vec<_>, _>>()?.into_iter().flatten().collect()
+    }
+
+    /// Render template group for each item of the batch (e.g. mail merge of one email to many recipients).
+    ///
+    /// Items are pairs of the context and the language preference (the language with its fallbacks). Results are
+    /// returned in the order of the items and each of them has the same members as the result of the
+    /// `render_group_with_preference()` method in order of the group definition. Languages of members are resolved
+    /// once for each distinct preference and reused for the following items with the same preference, but the
+    /// translation observer is notified for each item. Context overlays of the group are merged into the context of
+    /// the item once for all its members.
+    pub fn render_group_batch<I, P>(
+        &self,
+        group_key: &str,
+        items: I,
+    ) -> Result<Vec<GroupRenderResult>, TerariumError>
+        where
+            I: IntoIterator<Item=(Context, P)>,
+            P: Into<LanguagePreference>,
+    {
+        let group = self.groups.get(group_key).ok_or_else(|| TerariumError::GroupNotFound)?;
+        let group_fallbacks = self.group_fallbacks.get(group_key).map(|f| f.as_slice()).unwrap_or_default();
+        let mut resolutions = GroupResolutions::new();
+
+        Ok(items
+            .into_iter()
+            .map(|(context, preference)| {
+                let preference = preference.into();
+                self.resolve_batch_item(&mut resolutions, group, &preference, group_fallbacks)?;
+                self.render_batch_item(context, group, &resolutions[&preference], &preference)
+            })
+            .collect())
+    }
+
+    /// Render template group for each item of the batch like `render_group_batch()` with items rendered in parallel
+    /// by the `rayon` thread pool. Languages are resolved before rendering, so results are the same.
+    #[cfg(feature = "rayon")]
+    pub fn render_group_batch_par<I, P>(
+        &self,
+        group_key: &str,
+        items: I,
+    ) -> Result<Vec<GroupRenderResult>, TerariumError>
+        where
+            I: IntoIterator<Item=(Context, P)>,
+            P: Into<LanguagePreference>,
+    {
+        use rayon::prelude::*;
+
+        let group = self.groups.get(group_key).ok_or_else(|| TerariumError::GroupNotFound)?;
+        let group_fallbacks = self.group_fallbacks.get(group_key).map(|f| f.as_slice()).unwrap_or_default();
+        let mut resolutions = GroupResolutions::new();
+        let items = items
+            .into_iter()
+            .map(|(context, preference)| {
+                let preference = preference.into();
+                let resolution = self.resolve_batch_item(&mut resolutions, group, &preference, group_fallbacks);
+                (context, preference, resolution)
+            })
+            .collect::<Vec<_>>();
+
+        Ok(items
+            .into_par_iter()
+            .map(|(context, preference, resolution)| {
+                resolution?;
+                self.render_batch_item(context, group, &resolutions[&preference], &preference)
+            })
+            .collect())
+    }
+
+    /// Render template group with the same language for all members.
+    ///
+    /// The language is the first one of the `preference` (or the fallback languages configured for the group) which is
+    /// available for every mandatory member of the group. Members with the language-agnostic content are compatible
+    /// with any language, so the group is rendered even without requested languages when all its mandatory members have
+    /// such content. If there is no such language, the `NoCommonLanguage` error is returned. Optional members without
+    /// the language are left out.
+    ///
+    /// Fallback languages of member templates are not used, because they could select different languages for
+    /// different members.
+    pub fn render_group_consistent(
+        &self,
+        context: &Context,
+        group_key: &str,
+        preference: &LanguagePreference,
+    ) -> Result<HashMap<String, String>, TerariumError> {
+        let group = self.groups.get(group_key).ok_or_else(|| TerariumError::GroupNotFound)?;
+        let group_fallbacks = self.group_fallbacks.get(group_key).map(|f| f.as_slice()).unwrap_or_default();
+        let templates = group
+            .iter()
+            .map(|(member_key, member)| {
+                let template_key = &member.template_key;
+                let template = self.template_map.get(template_key).ok_or_else(|| TerariumError::TemplateNotFound)?;
+                Ok((member_key, member, template, self.wildcards.get(template_key)))
+            })
+            .collect::<Result<Vec<_>, TerariumError>>()?;
+
+        let candidates = self.language_candidates(preference.languages().iter().chain(group_fallbacks));
+        let primary = preference.primary().and_then(|_| candidates.first());
+        let supports = |template: &HashMap<String, String>, wildcard: Option<&String>, language: &String| {
+            template.contains_key(language) || wildcard.is_some()
+        };
+        let language = candidates.iter().find(|k| {
+            templates
+                .iter()
+                .all(|(_, member, template, wildcard)| member.optional || supports(template, *wildcard, k))
+        });
+        let wildcards_only = || templates.iter().all(|(_, member, _, wildcard)| member.optional || wildcard.is_some());
+        if language.is_none() && !(candidates.is_empty() && wildcards_only()) {
+            // Members without the primary language are reported as missing translations (all members when no language
+            // was requested), even when they have other candidate languages.
+            let primary = candidates.first();
+            for (_, member, template, wildcard) in templates.iter() {
+                if !primary.is_some_and(|k| supports(template, *wildcard, k)) {
+                    self.notify(TranslationEventKind::Missing, &member.template_key, preference, None);
+                }
+            }
+            return Err(TerariumError::NoCommonLanguage);
         }
 
+        let mut result = HashMap::<String, String>::new();
+        for (member_key, member, template, wildcard) in templates {
+            let resolved_language = language.and_then(|k| template.get_key_value(k)).map(|(k, _)| k);
+            let Some(content_key) = language.and_then(|k| template.get(k)).or(wildcard) else {
+                self.notify(TranslationEventKind::Missing, &member.template_key, preference, None);
+                continue;
+            };
+            let fallback_used = resolved_language.is_none() || resolved_language != primary;
+            if fallback_used {
+                let resolved_language = resolved_language.map(String::as_str);
+                self.notify(TranslationEventKind::Fallback, &member.template_key, preference, resolved_language);
+            }
+            let context = make_member_context(context, group, member_key);
+            let render_language = language.map(String::as_str).unwrap_or_default();
+            let content = self.render_content(context, content_key, render_language, preference, fallback_used)?;
+            result.insert(member_key.clone(), content);
+        }
         Ok(result)
     }
+
+    /// Render the template in every language available for it (e.g. for previews and translator review).
+    /// Result is HashMap where keys are languages and values are rendered templates. Fallbacks are not applied. The
+    /// language-agnostic content has no language, so it is not included (render it by the `render_template()` method).
+    /// The translation observer is notified like for other renders.
+    pub fn render_template_all_languages(
+        &self,
+        context: &Context,
+        template_key: &str,
+    ) -> Result<HashMap<String, String>, TerariumError> {
+        let template = self.template_map.get(template_key).ok_or_else(|| TerariumError::TemplateNotFound)?;
+        template
+            .iter()
+            .map(|(language, template_name)| {
+                let preference = LanguagePreference::new(language.clone());
+                let context = Cow::Borrowed(context);
+                let content = self.render_content(context, template_name, language, &preference, false)?;
+                Ok((language.clone(), content))
+            })
+            .collect()
+    }
+
+    /// Render the template group in every language available for any of its members (e.g. for previews and translator
+    /// review).
+    ///
+    /// Result is HashMap where keys are languages and values are reports of the render in the language. Fallbacks are
+    /// not applied, so mandatory members without content in the language (and without the language-agnostic content)
+    /// are reported as failed with the `LanguageNotFound` error and optional ones are left out. Missing contents and
+    /// language-agnostic contents are reported to the translation observer like by the `render_group_consistent()`
+    /// method. Group with language-agnostic contents only has no language, so the result is empty.
+    pub fn render_group_all_languages(
+        &self,
+        context: &Context,
+        group_key: &str,
+    ) -> Result<HashMap<String, GroupRenderReport>, TerariumError> {
+        let group = self.groups.get(group_key).ok_or_else(|| TerariumError::GroupNotFound)?;
+        Ok(self
+            .any_member_languages(group)?
+            .into_iter()
+            .map(|language| (language.to_owned(), self.render_group_in_language(context, group, language)))
+            .collect())
+    }
+
+    /// Get sorted list of languages available for the template.
+    /// The language-agnostic content is not included.
+    pub fn template_languages(&self, template_key: &str) -> Result<Vec<&str>, TerariumError> {
+        let template = self.template_map.get(template_key).ok_or_else(|| TerariumError::TemplateNotFound)?;
+        let mut languages = template.keys().map(|k| k.as_str()).collect::<Vec<_>>();
+        languages.sort_unstable();
+        Ok(languages)
+    }
+
+    /// Get sorted list of languages available for all mandatory members of the group.
+    /// Members with language-agnostic content accept any language, so they do not restrict the list. When no member
+    /// restricts the list (all mandatory members have language-agnostic content), languages of any member are returned.
+    pub fn group_languages(&self, group_key: &str) -> Result<Vec<&str>, TerariumError> {
+        let group = self.groups.get(group_key).ok_or_else(|| TerariumError::GroupNotFound)?;
+        let mut member_languages = group
+            .iter()
+            .filter(|(_, member)| !member.optional && !self.wildcards.contains_key(&member.template_key))
+            .map(|(_, member)| self.template_languages(&member.template_key));
+        let mut languages = match member_languages.next() {
+            Some(languages) => languages?,
+            None => return self.any_member_languages(group),
+        };
+        for other_languages in member_languages {
+            let other_languages = other_languages?;
+            languages.retain(|k| other_languages.contains(k));
+        }
+        Ok(languages)
+    }
+
+    /// Get sorted list of languages available for any member of the group.
+    fn any_member_languages(&self, group: &TemplateGroup) -> Result<Vec<&str>, TerariumError> {
+        let mut languages = Vec::new();
+        for (_, member) in group.iter() {
+            for language in self.template_languages(&member.template_key)? {
+                if !languages.contains(&language) {
+                    languages.push(language);
+                }
+            }
+        }
+        languages.sort_unstable();
+        Ok(languages)
+    }
+
+    /// Negotiate languages of the template with the HTTP `Accept-Language` header.
+    ///
+    /// Result contains languages available for the template ordered by the header preference. Requested languages are
+    /// matched case-insensitively from the most specific tag to the least specific one (`en-US` matches `en`).
+    /// The first language is the best match. The result can be used for rendering directly.
+    pub fn negotiate_template(
+        &self,
+        template_key: &str,
+        accept_language: &str,
+    ) -> Result<LanguagePreference, TerariumError> {
+        Ok(negotiate_languages(&self.template_languages(template_key)?, accept_language))
+    }
+
+    /// Negotiate languages of the group with the HTTP `Accept-Language` header.
+    /// Only languages available for all members are considered. See `negotiate_template()` for details.
+    pub fn negotiate_group(
+        &self,
+        group_key: &str,
+        accept_language: &str,
+    ) -> Result<LanguagePreference, TerariumError> {
+        Ok(negotiate_languages(&self.group_languages(group_key)?, accept_language))
+    }
+
+    /// Find the template content matching the most preferred language.
+    /// If no language matches, the language-agnostic content is used.
+    fn resolve_content(
+        &self,
+        template_key: &str,
+        preference: &LanguagePreference,
+        group_fallbacks: &[String],
+    ) -> Result<ResolvedContent<'_>, TerariumError> {
+        let template = self.template_map.get(template_key).ok_or_else(|| TerariumError::TemplateNotFound)?;
+        let template_fallbacks = self.template_fallbacks.get(template_key).map(|f| f.as_slice()).unwrap_or_default();
+        let mut languages = preference.languages().iter().chain(group_fallbacks).chain(template_fallbacks);
+        // Candidates are expanded only for language tags, so the plain language keys are matched without allocation.
+        let (found, primary) = if self.language_tags {
+            let candidates = self.language_candidates(languages);
+            let found = candidates.iter().find_map(|k| template.get_key_value(k));
+            (found, preference.primary().and_then(|_| candidates.into_iter().next()).map(Cow::Owned))
+        } else {
+            (languages.find_map(|k| template.get_key_value(k)), preference.primary().map(Cow::Borrowed))
+        };
+
+        let resolved = match found {
+            Some((language, template_name)) => ResolvedContent {
+                template_name,
+                language: Some(language),
+                fallback_used: primary.as_deref() != Some(language.as_str()),
+            },
+            None => match self.wildcards.get(template_key) {
+                Some(template_name) => ResolvedContent { template_name, language: None, fallback_used: true },
+                None => {
+                    self.notify(TranslationEventKind::Missing, template_key, preference, None);
+                    return Err(TerariumError::LanguageNotFound);
+                }
+            },
+        };
+        if resolved.fallback_used {
+            let resolved_language = resolved.language.map(String::as_str);
+            self.notify(TranslationEventKind::Fallback, template_key, preference, resolved_language);
+        }
+        Ok(resolved)
+    }
+
+    /// Resolve content of the group member.
+    /// Return `None` if the member is optional and there is no content for the requested languages.
+    fn resolve_member(
+        &self,
+        member: &GroupMember,
+        preference: &LanguagePreference,
+        group_fallbacks: &[String],
+    ) -> Result<Option<ResolvedContent<'_>>, TerariumError> {
+        match self.resolve_content(&member.template_key, preference, group_fallbacks) {
+            Ok(resolved) => Ok(Some(resolved)),
+            Err(TerariumError::LanguageNotFound) if member.optional => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Resolve contents of all members of the group.
+    /// Optional members without the content are left out.
+    fn resolve_group<'a>(
+        &'a self,
+        group: &'a TemplateGroup,
+        preference: &LanguagePreference,
+        group_fallbacks: &[String],
+    ) -> Result<Vec<(&'a String, ResolvedContent<'a>)>, TerariumError> {
+        let mut members = Vec::new();
+        for (member_key, member) in group.iter() {
+            if let Some(resolved) = self.resolve_member(member, preference, group_fallbacks)? {
+                members.push((member_key, resolved));
+            }
+        }
+        Ok(members)
+    }
+
+    /// Resolve contents of group members for the `preference` unless they are already in `resolutions`.
+    /// The translation observer is notified about the already resolved contents again.
+    fn resolve_batch_item<'a>(
+        &'a self,
+        resolutions: &mut GroupResolutions<'a>,
+        group: &'a TemplateGroup,
+        preference: &LanguagePreference,
+        group_fallbacks: &[String],
+    ) -> Result<(), TerariumError> {
+        match resolutions.get(preference) {
+            Some(members) => self.notify_resolved_group(group, members, preference),
+            None => {
+                let members = self.resolve_group(group, preference, group_fallbacks)?;
+                resolutions.insert(preference.clone(), members);
+            }
+        }
+        Ok(())
+    }
+
+    /// Notify the translation observer (if any) about events of the resolved group `members` like the
+    /// `resolve_group()` method does: fallbacks of resolved members and misses of left out optional members.
+    fn notify_resolved_group(
+        &self,
+        group: &TemplateGroup,
+        members: &[(&String, ResolvedContent)],
+        preference: &LanguagePreference,
+    ) {
+        if self.observer.is_none() {
+            return;
+        }
+        let mut members = members.iter().peekable();
+        for (member_key, member) in group.iter() {
+            let template_key = &member.template_key;
+            match members.next_if(|(resolved_key, _)| *resolved_key == member_key) {
+                Some((_, resolved)) if resolved.fallback_used => {
+                    let resolved_language = resolved.language.map(String::as_str);
+                    self.notify(TranslationEventKind::Fallback, template_key, preference, resolved_language);
+                }
+                Some(_) => {}
+                None => self.notify(TranslationEventKind::Missing, template_key, preference, None),
+            }
+        }
+    }
+
+    /// Render all members of the group in the `language` without fallbacks.
+    fn render_group_in_language(
+        &self,
+        context: &Context,
+        group: &TemplateGroup,
+        language: &str,
+    ) -> GroupRenderReport {
+        let preference = LanguagePreference::new(language);
+        let mut report = GroupRenderReport::default();
+        for (member_key, member) in group.iter() {
+            let template_key = &member.template_key;
+            let template_name = self.template_map.get(template_key).and_then(|template| template.get(language));
+            // The language-agnostic content is rendered as fallback like by the `render_group_consistent()` method.
+            let fallback_used = template_name.is_none();
+            let Some(template_name) = template_name.or_else(|| self.wildcards.get(template_key)) else {
+                self.notify(TranslationEventKind::Missing, template_key, &preference, None);
+                if !member.optional {
+                    report.failed.push((member_key.clone(), TerariumError::LanguageNotFound));
+                }
+                continue;
+            };
+            if fallback_used {
+                self.notify(TranslationEventKind::Fallback, template_key, &preference, None);
+            }
+            let context = make_member_context(context, group, member_key);
+            match self.render_content(context, template_name, language, &preference, fallback_used) {
+                Ok(content) => {
+                    report.rendered.push(member_key.clone(), content);
+                }
+                Err(err) => {
+                    report.failed.push((member_key.clone(), err));
+                }
+            }
+        }
+        report
+    }
+
+    /// Render resolved contents of the group `members` into the collection of member keys and contents.
+    fn render_resolved_group<B: FromIterator<(String, String)>>(
+        &self,
+        context: &Context,
+        group: &TemplateGroup,
+        members: &[(&String, ResolvedContent)],
+        preference: &LanguagePreference,
+    ) -> Result<B, TerariumError> {
+        members
+            .iter()
+            .map(|(member_key, resolved)| {
+                let context = make_member_context(context, group, member_key);
+                let language = resolved.render_language(preference);
+                let fallback_used = resolved.fallback_used;
+                let template_name = resolved.template_name;
+                let content = self.render_content(context, template_name, language, preference, fallback_used)?;
+                Ok(((*member_key).clone(), content))
+            })
+            .collect()
+    }
+
+    /// Render resolved contents of the group `members` for the batch item.
+    ///
+    /// The group context is merged into the item `context` once and members without their own context are rendered
+    /// with it directly, so the context is cloned only for members with the member context.
+    fn render_batch_item(
+        &self,
+        mut context: Context,
+        group: &TemplateGroup,
+        members: &[(&String, ResolvedContent)],
+        preference: &LanguagePreference,
+    ) -> GroupRenderResult {
+        if let Some(overlay) = group.context() {
+            context.extend(overlay.clone());
+        }
+        members
+            .iter()
+            .map(|(member_key, resolved)| {
+                let language = resolved.render_language(preference);
+                let fallback_used = resolved.fallback_used;
+                let template_name = resolved.template_name;
+                let content = match group.member_context(member_key) {
+                    Some(overlay) => {
+                        let mut context = context.clone();
+                        context.extend(overlay.clone());
+                        self.render_in_context(&mut context, template_name, language, preference, fallback_used)
+                    }
+                    None => self.render_in_context(&mut context, template_name, language, preference, fallback_used),
+                }?;
+                Ok(((*member_key).clone(), content))
+            })
+            .collect()
+    }
+
+    /// Notify the translation observer (if any) about the event.
+    fn notify(
+        &self,
+        kind: TranslationEventKind,
+        template_key: &str,
+        preference: &LanguagePreference,
+        resolved_language: Option<&str>,
+    ) {
+        if let Some(observer) = &self.observer {
+            observer(&TranslationEvent {
+                kind,
+                template_key,
+                requested_language: preference.primary(),
+                resolved_language,
+            });
+        }
+    }
+
+    /// Render the resolved content.
+    /// The language-agnostic content is rendered with the primary language of the `preference` as render language.
+    fn render_resolved(
+        &self,
+        context: Cow<Context>,
+        resolved: ResolvedContent,
+        preference: &LanguagePreference,
+    ) -> Result<RenderOutcome, TerariumError> {
+        let language = resolved.render_language(preference);
+        let fallback_used = resolved.fallback_used;
+        let content = self.render_content(context, resolved.template_name, language, preference, fallback_used)?;
+        Ok(RenderOutcome {
+            content,
+            language: resolved.language.cloned(),
+            template_name: resolved.template_name.to_owned(),
+            fallback_used: resolved.fallback_used,
+        })
+    }
+
+    /// Notify the translation observer (if any) when the template uses the locale-aware filters, but there is no
+    /// bundled locale for the render `language`.
+    fn notify_unsupported_locale(&self, template_name: &str, language: &str, preference: &LanguagePreference) {
+        if self.observer.is_none() || is_supported_language(language) {
+            return;
+        }
+        if let Some(template_key) = self.locale_templates.get(template_name) {
+            self.notify(TranslationEventKind::UnsupportedLocale, template_key, preference, Some(language));
+        }
+    }
+
+    /// Render the Tera template in the render `language`.
+    /// The language metadata variables (`__lang`, `__requested_lang`, `__fallback_used` and `__dir`) are inserted into
+    /// the `context`. The borrowed context is copied once, the owned one (e.g. with the member overlays) is reused.
+    fn render_content(
+        &self,
+        context: Cow<Context>,
+        template_name: &str,
+        language: &str,
+        preference: &LanguagePreference,
+        fallback_used: bool,
+    ) -> Result<String, TerariumError> {
+        self.render_in_context(&mut context.into_owned(), template_name, language, preference, fallback_used)
+    }
+
+    /// Render the Tera template in the render `language` with the language metadata variables inserted into the
+    /// `context`. See `render_content()` for details.
+    fn render_in_context(
+        &self,
+        context: &mut Context,
+        template_name: &str,
+        language: &str,
+        preference: &LanguagePreference,
+        fallback_used: bool,
+    ) -> Result<String, TerariumError> {
+        self.notify_unsupported_locale(template_name, language, preference);
+        insert_language_variables(context, language, preference, fallback_used);
+        Ok(self.tera.render(template_name, context)?)
+    }
+
+    /// Render the Tera template in the render `language` into the `writer`.
+    /// See `render_content()` for details.
+    fn render_content_to(
+        &self,
+        context: Cow<Context>,
+        resolved: ResolvedContent,
+        preference: &LanguagePreference,
+        writer: impl Write,
+    ) -> Result<(), TerariumError> {
+        let language = resolved.render_language(preference);
+        self.notify_unsupported_locale(resolved.template_name, language, preference);
+        let mut context = context.into_owned();
+        insert_language_variables(&mut context, language, preference, resolved.fallback_used);
+        Ok(self.tera.render_to(resolved.template_name, &context, writer)?)
+    }
+
+    /// Get language keys to try in order of preference.
+    /// When language tags are enabled, each language is expanded to chain of its less specific tags.
+    fn language_candidates<'a>(&self, languages: impl Iterator<Item=&'a String>) -> Vec<String> {
+        let mut candidates = Vec::<String>::new();
+        for language in languages {
+            let chain = if self.language_tags {
+                language_tag_chain(language)
+            } else {
+                vec![language.to_owned()]
+            };
+            chain.into_iter().for_each(|k| if !candidates.contains(&k) { candidates.push(k) });
+        }
+        candidates
+    }
+}
+
+
+/// Resolved contents of group members by language preference.
+type GroupResolutions<'a> = HashMap<LanguagePreference, Vec<(&'a String, ResolvedContent<'a>)>>;
+
+
+/// Template content selected for rendering.
+#[derive(Clone, Copy)]
+struct ResolvedContent<'a> {
+    /// Name of the internal Tera template.
+    template_name: &'a str,
+    /// Language of the content (`None` for the language-agnostic content).
+    language: Option<&'a String>,
+    /// The content does not match the primary language.
+    fallback_used: bool,
+}
+
+
+impl<'a> ResolvedContent<'a> {
+    /// Get language the content is rendered in.
+    /// The primary language of the `preference` is used for the language-agnostic content.
+    fn render_language(&self, preference: &'a LanguagePreference) -> &'a str {
+        self.language.map(|k| k.as_str()).or(preference.primary()).unwrap_or_default()
+    }
+}
+
+
+/// Select available languages matching languages of the `Accept-Language` header in order of preference.
+fn negotiate_languages(available: &[&str], accept_language: &str) -> LanguagePreference {
+    let available = available
+        .iter()
+        .map(|k| (normalize_language_tag(k).unwrap_or_else(|| k.to_string()).to_ascii_lowercase(), *k))
+        .collect::<Vec<_>>();
+    let mut result = Vec::<&str>::new();
+
+    for (requested, _) in parse_accept_language(accept_language) {
+        for tag in language_tag_chain(&requested) {
+            let tag = tag.to_ascii_lowercase();
+            for (normalized, k) in available.iter() {
+                if *normalized == tag && !result.contains(k) {
+                    result.push(k);
+                }
+            }
+        }
+    }
+    result.into_iter().collect()
+}
+
+
+/// Merge context overlays of the group and the member on top of the caller's `context`.
+/// The `context` is borrowed when there is no overlay.
+fn make_member_context<'a>(context: &'a Context, group: &TemplateGroup, member_key: &str) -> Cow<'a, Context> {
+    let member_context = group.member_context(member_key);
+    if group.context().is_none() && member_context.is_none() {
+        return Cow::Borrowed(context);
+    }
+    let mut context = context.clone();
+    group.context().into_iter().chain(member_context).for_each(|overlay| context.extend(overlay.clone()));
+    Cow::Owned(context)
+}
+
+
+/// Insert the language metadata variables into the `context`.
+fn insert_language_variables(
+    context: &mut Context,
+    language: &str,
+    preference: &LanguagePreference,
+    fallback_used: bool,
+) {
+    context.insert("__lang", language);
+    context.insert("__requested_lang", preference.primary().unwrap_or_default());
+    context.insert("__fallback_used", &fallback_used);
+    context.insert("__dir", text_direction(language));
+}
+
+
+/// Create language preference from the primary and optional fallback language.
+fn make_preference(language: &str, fallback_language: Option<&str>) -> LanguagePreference {
+    LanguagePreference::new(language).with_fallbacks(fallback_language)
 }
 
 
@@ -88,10 +928,22 @@ pub enum TerariumError {
     /// Requested group was not found.
     #[error("There is no group")]
     GroupNotFound,
+    /// No requested language is available for all members of the group.
+    #[error("No requested language is available for all group members")]
+    NoCommonLanguage,
 
     /// Error propagated from underlying `Tera` instance.
     #[error("Error when rendering template")]
     RenderingFailed(TeraError),
+    /// Writer for the rendered content cannot be created.
+    #[error("Unable to create writer")]
+    WriterFailed(IoError),
+    /// Mandatory field of the `TerariumGroup` struct has no rendered member.
+    #[error("Member {0} was not rendered")]
+    MissingMember(String),
+    /// Asynchronous render was cancelled before it finished (e.g. by the runtime shutdown).
+    #[error("Rendering was cancelled")]
+    RenderCancelled,
 }
 
 
@@ -106,7 +958,12 @@ impl From<TeraError> for TerariumError {
 #[derive(Default)]
 pub struct TerariumBuilder {
     templates: HashMap<String, Template>,
-    groups: HashMap<String, HashMap<String, String>>,
+    groups: HashMap<String, TemplateGroup>,
+    template_fallbacks: HashMap<String, Vec<String>>,
+    group_fallbacks: HashMap<String, Vec<String>>,
+    language_tags: bool,
+    observer: Option<Arc<TranslationObserver>>,
+    required_groups: Vec<(String, &'static [MemberField])>,
 }
 
 
@@ -120,44 +977,146 @@ impl TerariumBuilder {
 
     /// Add new group into new instance
     /// If group with same name exists, it is replaced.
-    pub fn add_group(&mut self, key: String, group: HashMap<String, String>) -> Result<(), TerariumBuilderError> {
+    pub fn add_group(&mut self, key: String, group: impl Into<TemplateGroup>) -> Result<(), TerariumBuilderError> {
+        let group = group.into();
         // Check templates exist
-        for (_, tpl_name) in group.iter() {
-            if !self.templates.contains_key(tpl_name) {
-                return Err(TerariumBuilderError::TemplateNotFound(tpl_name.to_owned()));
+        for (_, member) in group.iter() {
+            if !self.templates.contains_key(&member.template_key) {
+                return Err(TerariumBuilderError::TemplateNotFound(member.template_key.to_owned()));
             }
         }
 
+        // Check member contexts belong to members (e.g. no typo in the member key)
+        if let Some(member_key) = group.member_context_keys().find(|member_key| group.get(member_key).is_none()) {
+            return Err(TerariumBuilderError::UnknownContextMember(key, member_key.to_owned()));
+        }
+
         // Add group to lookup
         self.groups.insert(key, group);
         Ok(())
     }
 
+    /// Require the group to have members of the `TerariumGroup` struct.
+    /// The `build()` method fails when the group is missing, any field has no member (e.g. a typo in the member key)
+    /// or a mandatory field has no mandatory member.
+    pub fn require_group<T: TerariumGroup>(&mut self, group_key: String) {
+        self.required_groups.push((group_key, T::MEMBERS));
+    }
+
+    /// Set fallback languages of the template. These languages are tried in order when no language requested by the
+    /// caller is available.
+    pub fn set_template_fallbacks(&mut self, key: String, fallbacks: Vec<String>) -> Result<(), TerariumBuilderError> {
+        if !self.templates.contains_key(&key) {
+            return Err(TerariumBuilderError::TemplateNotFound(key));
+        }
+        self.template_fallbacks.insert(key, fallbacks);
+        Ok(())
+    }
+
+    /// Set fallback languages of the group. These languages are tried in order when no language requested by the
+    /// caller is available for a group member (before fallback languages of the member template).
+    pub fn set_group_fallbacks(&mut self, key: String, fallbacks: Vec<String>) -> Result<(), TerariumBuilderError> {
+        if !self.groups.contains_key(&key) {
+            return Err(TerariumBuilderError::GroupNotFound(key));
+        }
+        self.group_fallbacks.insert(key, fallbacks);
+        Ok(())
+    }
+
+    /// Add all templates and groups provided by the `source`.
+    /// Templates and groups with same keys are replaced.
+    pub fn add_source(&mut self, source: &impl TemplateSource) -> Result<(), TerariumBuilderError> {
+        for (key, contents) in source.templates().map_err(TerariumBuilderError::SourceError)? {
+            let template = Template::new(contents).map_err(|err| TerariumBuilderError::InvalidTemplate(key.clone(), err))?;
+            self.add_template(key, template)?;
+        }
+        for (key, group) in source.groups().map_err(TerariumBuilderError::SourceError)? {
+            self.add_group(key, group)?;
+        }
+        Ok(())
+    }
+
+    /// Enable or disable language keys handling as BCP 47 language tags (disabled by default).
+    /// When enabled, language keys of contents and requested languages are normalized (e.g. `EN-us` to `en-US`) and
+    /// the language resolution walks from the most specific tag to the least specific one (`de-AT` to `de`) before
+    /// the fallback language is tried.
+    pub fn set_language_tags(&mut self, enabled: bool) {
+        self.language_tags = enabled;
+    }
+
+    /// Set observer notified about every fallback (the template is rendered, but not in the primary requested
+    /// language), every miss (no content for any requested language) and every render of template using the
+    /// locale-aware filters in language without bundled locale data.
+    pub fn set_translation_observer(&mut self, observer: impl Fn(&TranslationEvent) + Send + Sync + 'static) {
+        self.observer = Some(Arc::new(observer));
+    }
+
     /// Build new `Terarium` instance based on stored templates and groups.
     pub fn build(self) -> Result<Terarium, TerariumBuilderError> {
-        let mut instance = Terarium::default();
+        let mut instance = Terarium {
+            template_fallbacks: self.template_fallbacks,
+            group_fallbacks: self.group_fallbacks,
+            language_tags: self.language_tags,
+            observer: self.observer,
+            ..Terarium::default()
+        };
+        register_filters(&mut instance.tera);
         let mut tera_template_id: u32 = 1;
 
         // build templates
         self.templates.into_iter().try_for_each(|(template_key, template)| {
+            instance.template_map.entry(template_key.clone()).or_default();
             template.collect_contents().into_iter().try_for_each(|content| {
                 let template_name = content.name.unwrap_or_else(|| format!("template#{}", tera_template_id));
                 tera_template_id += 1;
-                instance.tera.add_raw_template(&template_name, &content.content)?;
-
-                content.languages.into_iter().for_each(|language_key| {
-                    instance
+                instance
+                    .tera
+                    .add_raw_template(&template_name, &content.content)
+                    .map_err(|err| TerariumBuilderError::InvalidTemplateSyntax(template_key.clone(), err))?;
+                let tera_template = instance.tera.templates.get_mut(&template_name);
+                if tera_template.is_some_and(localize_template) {
+                    instance.locale_templates.insert(template_name.clone(), template_key.clone());
+                }
+
+                // Only one language-agnostic content can be selected, so other ones are reported.
+                if content.languages.is_empty()
+                    && instance.wildcards.insert(template_key.clone(), template_name.clone()).is_some() {
+                    return Err(TerariumBuilderError::DuplicatedWildcardContent(template_key.clone()));
+                }
+
+                content.languages.into_iter().try_for_each(|language_key| {
+                    let language_key = if self.language_tags {
+                        normalize_language_tag(&language_key)
+                            .ok_or_else(|| TerariumBuilderError::InvalidLanguageTag(language_key))?
+                    } else {
+                        language_key
+                    };
+                    let previous = instance
                         .template_map
                         .entry(template_key.clone())
                         .or_default()
                         .insert(language_key.clone(), template_name.clone());
-                });
-
-                Ok::<_, TerariumBuilderError>(())
+                    match previous {
+                        Some(_) => Err(TerariumBuilderError::DuplicatedLanguage(template_key.clone(), language_key)),
+                        None => Ok(()),
+                    }
+                })
             })?;
             Ok::<_, TerariumBuilderError>(())
         })?;
 
+        // check groups required by group structs
+        for (group_key, fields) in self.required_groups {
+            let Some(group) = self.groups.get(&group_key) else {
+                return Err(TerariumBuilderError::GroupNotFound(group_key));
+            };
+            for field in fields.iter() {
+                if !group.get(field.member_key).is_some_and(|member| field.optional || !member.optional) {
+                    return Err(TerariumBuilderError::MissingGroupMember(group_key, field.member_key.to_owned()));
+                }
+            }
+        }
+
         instance.groups = self.groups;
         Ok(instance)
     }
@@ -167,18 +1126,40 @@ impl TerariumBuilder {
 /// Simplify building template groups.
 #[derive(Clone, Default)]
 pub struct TemplateGroupBuilder {
-    group: HashMap<String, String>,
+    group: TemplateGroup,
 }
 
 impl TemplateGroupBuilder {
     /// Add new member to group.
     pub fn add_member(mut self, member_key: String, template_key: String) -> Self {
-        self.group.insert(member_key, template_key);
+        self.group.add_member(member_key, GroupMember::new(template_key));
+        self
+    }
+
+    /// Add new optional member to group.
+    /// The member is left out of the render result when its template has no content for requested languages.
+    pub fn add_optional_member(mut self, member_key: String, template_key: String) -> Self {
+        self.group.add_member(member_key, GroupMember::new_optional(template_key));
         self
     }
 
-    /// Build the group spec.
-    pub fn build(self) -> HashMap<String, String> {
+    /// Set context overlay applied to all members of the group.
+    /// Variables of the overlay overwrite variables of the caller's context.
+    pub fn set_context(mut self, context: Context) -> Self {
+        self.group.set_context(context);
+        self
+    }
+
+    /// Set context overlay applied to the member identified by the `member_key`.
+    /// Variables of the overlay overwrite variables of the caller's context and the group context. The member has to be
+    /// in the group, otherwise the group is rejected by the `TerariumBuilder::add_group()` method.
+    pub fn set_member_context(mut self, member_key: String, context: Context) -> Self {
+        self.group.set_member_context(member_key, context);
+        self
+    }
+
+    /// Build the group with members in order of insertion, their optional flags and context overlays.
+    pub fn build(self) -> TemplateGroup {
         self.group
     }
 }
@@ -189,10 +1170,37 @@ impl TemplateGroupBuilder {
 pub enum TerariumBuilderError {
     /// Cannot build template in underlying `Tera` instance.
     #[error("Unable to build template")]
-    TemplateBuildingError(TeraError),
+    TemplateBuildingError(#[source] TeraError),
     /// Template was not found (when building group).
     #[error("Cannot build template groups - some templates are missing")]
     TemplateNotFound(String),
+    /// Group was not found (when configuring group).
+    #[error("There is no group {0}")]
+    GroupNotFound(String),
+    /// Contents provided by template source cannot form a template.
+    #[error("Invalid template {0}")]
+    InvalidTemplate(String, #[source] TemplateError),
+    /// Language key is not well-formed BCP 47 language tag (when language tags are enabled).
+    #[error("Invalid language tag {0}")]
+    InvalidLanguageTag(String),
+    /// More contents of the template have same language key after normalization.
+    #[error("Template {0} has more contents for language {1}")]
+    DuplicatedLanguage(String, String),
+    /// More contents of the template have no language, so only one of them could be selected.
+    #[error("Template {0} has more language-agnostic contents")]
+    DuplicatedWildcardContent(String),
+    /// Template source failed to provide templates or groups.
+    #[error("Unable to read template source")]
+    SourceError(#[source] SourceError),
+    /// Content of the template cannot be parsed by `Tera`.
+    #[error("Invalid syntax of template {0}")]
+    InvalidTemplateSyntax(String, #[source] TeraError),
+    /// Group has no member required by the `TerariumGroup` struct or the member of the mandatory field is optional.
+    #[error("Group {0} has no matching member {1}")]
+    MissingGroupMember(String, String),
+    /// Group has context overlay of the member which is not in the group.
+    #[error("Group {0} has context of unknown member {1}")]
+    UnknownContextMember(String, String),
 }
 
 
@@ -220,13 +1228,13 @@ impl TerariumBuilder {
 
     /// Get group defined by the `key`.
     /// Return `None` if no group defined by the `key` is found.
-    pub fn get_group(&mut self, key: &String) -> Option<&mut HashMap<String, String>> {
+    pub fn get_group(&mut self, key: &String) -> Option<&mut TemplateGroup> {
         self.groups.get_mut(key)
     }
 
     /// Remove group defined by the `key` from the builder and return it.
     /// Returns `None` if no group with given `key` is defined.
-    pub fn remove_group(&mut self, key: &String) -> Option<HashMap<String, String>> {
+    pub fn remove_group(&mut self, key: &String) -> Option<TemplateGroup> {
         self.groups.remove(key)
     }
 }
@@ -267,7 +1275,7 @@ mod tests {
             let grp = instance.get_group(&"1".to_owned());
             assert!(grp.is_some());
             let grp = grp.unwrap();
-            assert_eq!(grp.clone(), HashMap::<String, String>::from([("1".to_owned(), "1".to_owned())]));
+            assert_eq!(grp.clone(), TemplateGroup::from(HashMap::from([("1".to_owned(), "1".to_owned())])));
 
             instance.remove_group(&"1".to_owned());
             assert!(instance.get_group(&"1".to_owned()).is_none())
@@ -295,6 +1303,8 @@ mod tests {
     }
 
     mod terarium {
+        use std::sync::Mutex;
+
         use crate::Content;
 
         use super::*;
@@ -316,6 +1326,7 @@ mod tests {
         }
 
         #[test]
+        #[allow(clippy::match_like_matches_macro)]
         fn render_template_without_matching_language() {
             let instance = make_instance();
             let ctx = make_context();
@@ -350,6 +1361,7 @@ mod tests {
         }
 
         #[test]
+        #[allow(clippy::match_like_matches_macro)]
         fn render_group_when_invalid_language() {
             let instance = make_instance();
             let context = make_context();
@@ -385,12 +1397,879 @@ mod tests {
             assert_eq!(result.as_str(), "This is content foo This is nested bar");
         }
 
-        fn make_instance() -> Terarium {
-            let mut builder = TerariumBuilder::default();
-
-            let mut tpl_a = Template::default();
-            tpl_a.add_content(Content::new("template_a cs {{name}}".to_owned(), vec!["cs".to_owned()])).unwrap();
-            tpl_a.add_content(Content::new("template_a en {{name}}".to_owned(), vec!["en".to_owned()])).unwrap();
+        #[test]
+        fn render_template_with_language_tags() {
+            let instance = make_tagged_instance();
+            let ctx = make_context();
+            assert_eq!(instance.render_template(&ctx, "template_a", "PT-br", None).unwrap(), "template_a pt john");
+            assert_eq!(instance.render_template(&ctx, "template_a", "de-AT", None).unwrap(), "template_a de-AT john");
+            assert_eq!(instance.render_template(&ctx, "template_a", "de-CH", Some("pt")).unwrap(), "template_a de john");
+            assert_eq!(instance.render_template(&ctx, "template_a", "fr-FR", Some("pt-PT")).unwrap(), "template_a pt john");
+        }
+
+        #[test]
+        fn render_template_without_language_tags() {
+            let instance = make_instance();
+            let result = instance.render_template(&make_context(), "template_a", "en-US", None);
+            assert!(matches!(result.unwrap_err(), TerariumError::LanguageNotFound));
+        }
+
+        #[test]
+        fn invalid_language_tag() {
+            let mut builder = TerariumBuilder::default();
+            builder.set_language_tags(true);
+            builder.add_template(
+                "template_a".to_owned(),
+                Template::new(vec![Content::new("foo".to_owned(), vec!["en--US".to_owned()])]).unwrap(),
+            ).unwrap();
+            assert!(matches!(builder.build().err().unwrap(), TerariumBuilderError::InvalidLanguageTag(_)));
+        }
+
+        #[test]
+        fn duplicated_normalized_language_tag() {
+            let mut builder = TerariumBuilder::default();
+            builder.set_language_tags(true);
+            builder.add_template(
+                "template_a".to_owned(),
+                Template::new(vec![
+                    Content::new("foo".to_owned(), vec!["en".to_owned()]),
+                    Content::new("bar".to_owned(), vec!["EN".to_owned()]),
+                ]).unwrap(),
+            ).unwrap();
+            assert!(matches!(builder.build().err().unwrap(), TerariumBuilderError::DuplicatedLanguage(_, _)));
+        }
+
+        #[test]
+        fn render_template_with_preference() {
+            let instance = make_instance();
+            let ctx = make_context();
+            let preference = LanguagePreference::new("sk").with_fallback("de").with_fallback("en").with_fallback("cs");
+            assert_eq!(instance.render_template_with_preference(&ctx, "template_a", &preference).unwrap(), "template_a en john");
+
+            let result = instance.render_template_with_preference(&ctx, "template_a", &LanguagePreference::from("sk"));
+            assert!(matches!(result.unwrap_err(), TerariumError::LanguageNotFound));
+        }
+
+        #[test]
+        fn render_group_with_preference() {
+            let instance = make_instance();
+            let preference = ["sk", "cs", "en"].into_iter().collect();
+            let group_result = instance.render_group_with_preference(&make_context(), "group_a", &preference).unwrap();
+            assert_eq!(group_result.get("A").unwrap(), "template_a cs john");
+            assert_eq!(group_result.get("B").unwrap(), "template_b en doe");
+        }
+
+        #[test]
+        fn configured_fallbacks() {
+            let mut builder = make_builder();
+            builder.set_template_fallbacks("template_a".to_owned(), vec!["en".to_owned()]).unwrap();
+            builder.set_group_fallbacks("group_a".to_owned(), vec!["cs".to_owned(), "en".to_owned()]).unwrap();
+            let instance = builder.build().unwrap();
+            let ctx = make_context();
+
+            assert_eq!(instance.render_template(&ctx, "template_a", "sk", None).unwrap(), "template_a en john");
+            assert_eq!(instance.render_template(&ctx, "template_a", "sk", Some("cs")).unwrap(), "template_a cs john");
+
+            let group_result = instance.render_group(&ctx, "group_a", "sk", None).unwrap();
+            assert_eq!(group_result.get("A").unwrap(), "template_a cs john");
+            assert_eq!(group_result.get("B").unwrap(), "template_b en doe");
+        }
+
+        #[test]
+        fn configure_fallbacks_of_missing_items() {
+            let mut builder = make_builder();
+            let result = builder.set_template_fallbacks("missing".to_owned(), vec![]);
+            assert!(matches!(result.unwrap_err(), TerariumBuilderError::TemplateNotFound(_)));
+            let result = builder.set_group_fallbacks("missing".to_owned(), vec![]);
+            assert!(matches!(result.unwrap_err(), TerariumBuilderError::GroupNotFound(_)));
+        }
+
+        #[test]
+        fn render_group_consistent() {
+            let instance = make_instance();
+            let preference = LanguagePreference::new("cs").with_fallback("en");
+            let group_result = instance.render_group_consistent(&make_context(), "group_a", &preference).unwrap();
+            assert_eq!(group_result.get("A").unwrap(), "template_a en john");
+            assert_eq!(group_result.get("B").unwrap(), "template_b en doe");
+        }
+
+        #[test]
+        fn render_group_consistent_with_group_fallbacks() {
+            let mut builder = make_builder();
+            builder.set_group_fallbacks("group_a".to_owned(), vec!["en".to_owned()]).unwrap();
+            let instance = builder.build().unwrap();
+            let group_result = instance.render_group_consistent(&make_context(), "group_a", &"cs".into()).unwrap();
+            assert_eq!(group_result.get("A").unwrap(), "template_a en john");
+        }
+
+        #[test]
+        fn render_group_consistent_without_common_language() {
+            let instance = make_instance();
+            let result = instance.render_group_consistent(&make_context(), "group_a", &"cs".into());
+            assert!(matches!(result.unwrap_err(), TerariumError::NoCommonLanguage));
+        }
+
+        #[test]
+        fn render_group_consistent_without_template_fallbacks() {
+            let mut builder = make_builder();
+            builder.set_template_fallbacks("template_b".to_owned(), vec!["en".to_owned()]).unwrap();
+            let instance = builder.build().unwrap();
+            let result = instance.render_group_with_preference(&make_context(), "group_a", &"cs".into()).unwrap();
+            assert_eq!(result["B"], "template_b en doe");
+            let result = instance.render_group_consistent(&make_context(), "group_a", &"cs".into());
+            assert!(matches!(result.unwrap_err(), TerariumError::NoCommonLanguage));
+        }
+
+        #[test]
+        fn render_group_consistent_with_wildcards_only() {
+            let mut builder = make_wildcard_builder();
+            builder.add_group(
+                "group_d".to_owned(),
+                TemplateGroupBuilder::default()
+                    .add_member("D".to_owned(), "template_d".to_owned())
+                    .add_optional_member("A".to_owned(), "template_a".to_owned())
+                    .build(),
+            ).unwrap();
+            let instance = builder.build().unwrap();
+            let preference = LanguagePreference::default();
+            let group_result = instance.render_group_consistent(&make_context(), "group_d", &preference).unwrap();
+            assert_eq!(group_result, HashMap::from([("D".to_owned(), "template_d any".to_owned())]));
+            let group_result = instance.render_group_consistent(&make_context(), "group_d", &"cs".into()).unwrap();
+            assert_eq!(group_result.len(), 2);
+
+            let result = instance.render_group_consistent(&make_context(), "group_b", &preference);
+            assert!(matches!(result.unwrap_err(), TerariumError::NoCommonLanguage));
+        }
+
+        #[test]
+        fn render_wildcard_content() {
+            let instance = make_wildcard_instance();
+            let ctx = make_context();
+            assert_eq!(instance.render_template(&ctx, "template_c", "cs", None).unwrap(), "template_c cs");
+            assert_eq!(instance.render_template(&ctx, "template_c", "de", Some("en")).unwrap(), "template_c any");
+            assert_eq!(instance.render_template(&ctx, "template_d", "de", None).unwrap(), "template_d any");
+        }
+
+        #[test]
+        fn render_group_consistent_with_wildcard() {
+            let instance = make_wildcard_instance();
+            let group_result = instance.render_group_consistent(&make_context(), "group_b", &"en".into()).unwrap();
+            assert_eq!(group_result.get("A").unwrap(), "template_a en john");
+            assert_eq!(group_result.get("C").unwrap(), "template_c any");
+            assert_eq!(instance.group_languages("group_b").unwrap(), vec!["cs", "en"]);
+            assert_eq!(instance.template_languages("template_d").unwrap(), Vec::<&str>::new());
+        }
+
+        #[test]
+        fn render_template_detailed() {
+            let instance = make_instance();
+            let outcome = instance.render_template_detailed(&make_context(), "template_a", &"cs".into()).unwrap();
+            assert_eq!(outcome.content, "template_a cs john");
+            assert_eq!(outcome.language, Some("cs".to_owned()));
+            assert!(!outcome.fallback_used);
+
+            let preference = LanguagePreference::new("de").with_fallback("en");
+            let outcome = instance.render_template_detailed(&make_context(), "template_a", &preference).unwrap();
+            assert_eq!(outcome.content, "template_a en john");
+            assert_eq!(outcome.language, Some("en".to_owned()));
+            assert!(outcome.fallback_used);
+
+            let outcome = make_wildcard_instance()
+                .render_template_detailed(&make_context(), "template_d", &"en".into())
+                .unwrap();
+            assert_eq!(outcome.language, None);
+            assert!(outcome.fallback_used);
+        }
+
+        #[test]
+        fn render_template_detailed_with_language_tags() {
+            let instance = make_tagged_instance();
+            let outcome = instance.render_template_detailed(&make_context(), "template_a", &"DE-at".into()).unwrap();
+            assert_eq!(outcome.language, Some("de-AT".to_owned()));
+            assert!(!outcome.fallback_used);
+
+            let outcome = instance.render_template_detailed(&make_context(), "template_a", &"pt-BR".into()).unwrap();
+            assert_eq!(outcome.language, Some("pt".to_owned()));
+            assert!(outcome.fallback_used);
+        }
+
+        #[test]
+        fn render_group_detailed() {
+            let instance = make_instance();
+            let preference = LanguagePreference::new("cs").with_fallback("en");
+            let group_result = instance.render_group_detailed(&make_context(), "group_a", &preference).unwrap();
+            assert_eq!(group_result["A"].language, Some("cs".to_owned()));
+            assert!(!group_result["A"].fallback_used);
+            assert_eq!(group_result["B"].content, "template_b en doe");
+            assert_eq!(group_result["B"].language, Some("en".to_owned()));
+            assert!(group_result["B"].fallback_used);
+            assert_ne!(group_result["A"].template_name, group_result["B"].template_name);
+        }
+
+        #[test]
+        fn observe_translations() {
+            let events = Arc::new(Mutex::new(Vec::<(TranslationEventKind, String, Option<String>)>::new()));
+            let observed_events = events.clone();
+            let mut builder = make_builder();
+            builder.set_translation_observer(move |event| {
+                observed_events.lock().unwrap().push((
+                    event.kind,
+                    event.template_key.to_owned(),
+                    event.resolved_language.map(|k| k.to_owned()),
+                ));
+            });
+            let instance = builder.build().unwrap();
+            let ctx = make_context();
+
+            instance.render_template(&ctx, "template_a", "cs", None).unwrap();
+            instance.render_template(&ctx, "template_a", "de", Some("en")).unwrap();
+            instance.render_template(&ctx, "template_b", "cs", None).unwrap_err();
+            instance.render_group_consistent(&ctx, "group_a", &"cs".into()).unwrap_err();
+            assert_eq!(*events.lock().unwrap(), vec![
+                (TranslationEventKind::Fallback, "template_a".to_owned(), Some("en".to_owned())),
+                (TranslationEventKind::Missing, "template_b".to_owned(), None),
+                (TranslationEventKind::Missing, "template_b".to_owned(), None),
+            ]);
+        }
+
+        #[test]
+        fn observe_missing_common_language() {
+            let events = Arc::new(Mutex::new(Vec::<(TranslationEventKind, String)>::new()));
+            let observed_events = events.clone();
+            let mut builder = make_builder();
+            builder.add_template(
+                "template_cs".to_owned(),
+                Template::new(vec![Content::new("template_cs".to_owned(), vec!["cs".to_owned()])]).unwrap(),
+            ).unwrap();
+            builder.add_group(
+                "group_x".to_owned(),
+                TemplateGroupBuilder::default()
+                    .add_member("A".to_owned(), "template_cs".to_owned())
+                    .add_member("B".to_owned(), "template_b".to_owned())
+                    .build(),
+            ).unwrap();
+            builder.set_translation_observer(move |event| {
+                observed_events.lock().unwrap().push((event.kind, event.template_key.to_owned()));
+            });
+            let instance = builder.build().unwrap();
+
+            let preference = LanguagePreference::new("cs").with_fallback("en");
+            let result = instance.render_group_consistent(&make_context(), "group_x", &preference);
+            assert!(matches!(result.unwrap_err(), TerariumError::NoCommonLanguage));
+            assert_eq!(*events.lock().unwrap(), vec![(TranslationEventKind::Missing, "template_b".to_owned())]);
+        }
+
+        #[test]
+        fn observe_consistent_group_fallbacks() {
+            let events = Arc::new(Mutex::new(Vec::<String>::new()));
+            let observed_events = events.clone();
+            let mut builder = make_builder();
+            builder.set_translation_observer(move |event| {
+                assert_eq!(event.requested_language, Some("de"));
+                observed_events.lock().unwrap().push(event.template_key.to_owned());
+            });
+            let instance = builder.build().unwrap();
+
+            let preference = LanguagePreference::new("de").with_fallback("en");
+            instance.render_group_consistent(&make_context(), "group_a", &preference).unwrap();
+            let mut events = events.lock().unwrap().clone();
+            events.sort();
+            assert_eq!(events, vec!["template_a".to_owned(), "template_b".to_owned()]);
+        }
+
+        #[test]
+        fn render_with_locale_filters() {
+            let mut builder = TerariumBuilder::default();
+            let content = "{{ price | format_number }}";
+            builder.add_template(
+                "price".to_owned(),
+                Template::new(vec![
+                    Content::new(content.to_owned(), vec!["cs".to_owned(), "en".to_owned()]),
+                    Content::new_wildcard("{{ price | format_currency(currency='EUR') }}".to_owned()),
+                ]).unwrap(),
+            ).unwrap();
+            let instance = builder.build().unwrap();
+            let mut ctx = Context::new();
+            ctx.insert("price", &1234.5);
+
+            assert_eq!(instance.render_template(&ctx, "price", "cs", None).unwrap(), "1\u{a0}234,5");
+            assert_eq!(instance.render_template(&ctx, "price", "en", None).unwrap(), "1,234.5");
+            assert_eq!(instance.render_template(&ctx, "price", "de", None).unwrap(), "1.234,50\u{a0}€");
+            assert_eq!(instance.render_template(&ctx, "price", "xx", None).unwrap(), "€1,234.50");
+        }
+
+        #[test]
+        fn observe_unsupported_locale() {
+            let events = Arc::new(Mutex::new(Vec::new()));
+            let observed_events = events.clone();
+            let mut builder = TerariumBuilder::default();
+            builder.add_template(
+                "price".to_owned(),
+                Template::new(vec![
+                    Content::new_wildcard("{{ price | format_number }}".to_owned()),
+                ]).unwrap(),
+            ).unwrap();
+            builder.add_template(
+                "name".to_owned(),
+                Template::new(vec![Content::new_wildcard("{{ name }}".to_owned())]).unwrap(),
+            ).unwrap();
+            builder.set_translation_observer(move |event| {
+                if event.kind == TranslationEventKind::UnsupportedLocale {
+                    let language = event.resolved_language.map(|k| k.to_owned());
+                    observed_events.lock().unwrap().push((event.template_key.to_owned(), language));
+                }
+            });
+            let instance = builder.build().unwrap();
+            let mut ctx = Context::new();
+            ctx.insert("price", &1.5);
+            ctx.insert("name", "John");
+
+            assert_eq!(instance.render_template(&ctx, "price", "de-AT", None).unwrap(), "1,5");
+            assert_eq!(instance.render_template(&ctx, "price", "ja", None).unwrap(), "1.5");
+            assert_eq!(instance.render_template(&ctx, "name", "ja", None).unwrap(), "John");
+            assert_eq!(*events.lock().unwrap(), vec![("price".to_owned(), Some("ja".to_owned()))]);
+        }
+
+        #[test]
+        fn inject_language_variables() {
+            let mut builder = TerariumBuilder::default();
+            let content = "{{ __lang }} {{ __requested_lang }} {{ __fallback_used }} {{ __dir }}";
+            builder.add_template(
+                "meta".to_owned(),
+                Template::new(vec![
+                    Content::new(content.to_owned(), vec!["en".to_owned(), "ar".to_owned()]),
+                    Content::new_wildcard(content.to_owned()),
+                ]).unwrap(),
+            ).unwrap();
+            builder.add_group(
+                "group".to_owned(),
+                TemplateGroupBuilder::default().add_member("meta".to_owned(), "meta".to_owned()).build(),
+            ).unwrap();
+            let instance = builder.build().unwrap();
+            let ctx = Context::new();
+
+            assert_eq!(instance.render_template(&ctx, "meta", "ar", None).unwrap(), "ar ar false rtl");
+            assert_eq!(instance.render_template(&ctx, "meta", "cs", Some("en")).unwrap(), "en cs true ltr");
+            assert_eq!(instance.render_template(&ctx, "meta", "he", None).unwrap(), "he he true rtl");
+            assert_eq!(instance.render_group(&ctx, "group", "en", None).unwrap()["meta"], "en en false ltr");
+            let group_result = instance.render_group_consistent(&ctx, "group", &"fa".into()).unwrap();
+            assert_eq!(group_result["meta"], "fa fa true rtl");
+            assert!(!ctx.contains_key("__lang"));
+        }
+
+        #[cfg(feature = "rayon")]
+        #[test]
+        fn render_group_par() {
+            let instance = make_instance();
+            let preference = LanguagePreference::new("cs").with_fallback("en");
+            let group_result = instance.render_group_par(&make_context(), "group_a", &preference).unwrap();
+            assert_eq!(group_result, instance.render_group_with_preference(&make_context(), "group_a", &preference).unwrap());
+
+            let result = instance.render_group_par(&make_context(), "group_a", &"cs".into());
+            assert!(matches!(result.unwrap_err(), TerariumError::LanguageNotFound));
+            let result = instance.render_group_par(&make_context(), "group_x", &"cs".into());
+            assert!(matches!(result.unwrap_err(), TerariumError::GroupNotFound));
+        }
+
+        #[cfg(feature = "rayon")]
+        #[test]
+        fn render_group_par_errors() {
+            let mut builder = make_builder();
+            for key in ["broken_a", "broken_b"] {
+                let content = format!("{{{{ {}_variable }}}}", key);
+                let template = Template::new(vec![Content::new_named(content, vec!["cs".to_owned()], key.to_owned())]);
+                builder.add_template(key.to_owned(), template.unwrap()).unwrap();
+            }
+            builder.add_group(
+                "group_b".to_owned(),
+                TemplateGroupBuilder::default()
+                    .add_member("A".to_owned(), "broken_a".to_owned())
+                    .add_member("B".to_owned(), "broken_b".to_owned())
+                    .add_member("C".to_owned(), "template_b".to_owned())
+                    .build(),
+            ).unwrap();
+            builder.add_group(
+                "group_c".to_owned(),
+                TemplateGroupBuilder::default()
+                    .add_member("B".to_owned(), "broken_b".to_owned())
+                    .add_member("A".to_owned(), "broken_a".to_owned())
+                    .build(),
+            ).unwrap();
+            let instance = builder.build().unwrap();
+            let preference = LanguagePreference::new("cs");
+
+            for _ in 0..20 {
+                let result = instance.render_group_par(&make_context(), "group_b", &preference);
+                assert!(matches!(result.unwrap_err(), TerariumError::LanguageNotFound));
+                let err = instance.render_group_par(&make_context(), "group_c", &preference).unwrap_err();
+                let sequential_err = instance.render_group_with_preference(&make_context(), "group_c", &preference);
+                assert!(matches!(err, TerariumError::RenderingFailed(_)));
+                assert_eq!(format!("{:?}", err), format!("{:?}", sequential_err.unwrap_err()));
+            }
+        }
+
+        #[test]
+        fn render_group_batch() {
+            let observed = Arc::new(Mutex::new(0));
+            let observed_clone = observed.clone();
+            let mut builder = make_builder();
+            builder.set_translation_observer(move |_| *observed_clone.lock().unwrap() += 1);
+            let instance = builder.build().unwrap();
+            let items = ["john", "jane", "jim"].into_iter().map(|name| {
+                let mut ctx = make_context();
+                ctx.insert("name", name);
+                let preference = match name {
+                    "jim" => LanguagePreference::new("cs"),
+                    _ => LanguagePreference::new("de").with_fallback("en"),
+                };
+                (ctx, preference)
+            });
+
+            let results = instance.render_group_batch("group_a", items).unwrap();
+            assert_eq!(results.len(), 3);
+            assert_eq!(&results[0].as_ref().unwrap()["A"], "template_a en john");
+            assert_eq!(&results[1].as_ref().unwrap()["A"], "template_a en jane");
+            assert!(matches!(results[2].as_ref().unwrap_err(), TerariumError::LanguageNotFound));
+            // Fallbacks of both members are reported for each of the first two items, the miss of template_b once.
+            assert_eq!(*observed.lock().unwrap(), 5);
+
+            let result = instance.render_group_batch("group_x", [(make_context(), "en")]);
+            assert!(matches!(result.unwrap_err(), TerariumError::GroupNotFound));
+        }
+
+        #[cfg(feature = "rayon")]
+        #[test]
+        fn render_group_batch_par() {
+            let instance = make_instance();
+            let items = (0..20).map(|i| {
+                let mut ctx = make_context();
+                ctx.insert("name", &i);
+                (ctx, if i % 2 == 0 { "en" } else { "cs" })
+            }).collect::<Vec<_>>();
+
+            let results = instance.render_group_batch_par("group_a", items.clone()).unwrap();
+            let sequential_results = instance.render_group_batch("group_a", items).unwrap();
+            assert_eq!(results.len(), 20);
+            for (result, sequential_result) in results.iter().zip(sequential_results.iter()) {
+                assert_eq!(result.as_ref().ok(), sequential_result.as_ref().ok());
+            }
+            assert_eq!(&results[4].as_ref().unwrap()["A"], "template_a en 4");
+            assert!(matches!(results[5].as_ref().unwrap_err(), TerariumError::LanguageNotFound));
+        }
+
+        #[test]
+        fn render_group_ordered() {
+            let mut builder = make_builder();
+            builder.add_group(
+                "group_z".to_owned(),
+                TemplateGroupBuilder::default()
+                    .add_member("Z".to_owned(), "template_a".to_owned())
+                    .add_member("B".to_owned(), "template_b".to_owned())
+                    .add_member("M".to_owned(), "template_a".to_owned())
+                    .add_optional_member("A".to_owned(), "template_b".to_owned())
+                    .build(),
+            ).unwrap();
+            let instance = builder.build().unwrap();
+
+            let result = instance.render_group_ordered(&make_context(), "group_z", &"en".into()).unwrap();
+            let member_keys = result.iter().map(|(member_key, _)| member_key).collect::<Vec<_>>();
+            assert_eq!(member_keys, ["Z", "B", "M", "A"]);
+            assert_eq!(&result["B"], "template_b en doe");
+            assert_eq!(result.get("X"), None);
+            let result = HashMap::from(result);
+            assert_eq!(result, instance.render_group(&make_context(), "group_z", "en", None).unwrap());
+
+            let result = instance.render_group_ordered(&make_context(), "group_z", &"cs".into());
+            assert!(matches!(result.unwrap_err(), TerariumError::LanguageNotFound));
+            let result = instance.render_group_ordered(&make_context(), "group_x", &"en".into());
+            assert!(matches!(result.unwrap_err(), TerariumError::GroupNotFound));
+        }
+
+        #[test]
+        fn render_group_as() {
+            let instance = make_optional_instance();
+            let preference = LanguagePreference::new("cs");
+            let group = instance.render_group_as::<OptionalGroup>(&make_context(), "group_o", &preference).unwrap();
+            assert_eq!(group, OptionalGroup { a: "template_a cs john".to_owned(), b: None });
+            let group = instance.render_group_as::<OptionalGroup>(&make_context(), "group_o", &"en".into()).unwrap();
+            assert_eq!(group.b.as_deref(), Some("template_b en doe"));
+
+            let result = instance.render_group_as::<OptionalGroup>(&make_context(), "group_x", &"en".into());
+            assert!(matches!(result.unwrap_err(), TerariumError::GroupNotFound));
+        }
+
+        #[test]
+        fn require_group() {
+            let mut builder = make_builder();
+            builder.require_group::<OptionalGroup>("group_a".to_owned());
+            assert!(builder.build().is_ok());
+
+            let mut builder = make_builder();
+            builder.add_group(
+                "group_o".to_owned(),
+                TemplateGroupBuilder::default()
+                    .add_optional_member("A".to_owned(), "template_a".to_owned())
+                    .build(),
+            ).unwrap();
+            builder.require_group::<OptionalGroup>("group_o".to_owned());
+            let result = builder.build();
+            assert!(matches!(result, Err(TerariumBuilderError::MissingGroupMember(group, member))
+                if group == "group_o" && member == "A"));
+
+            let mut builder = make_builder();
+            builder.add_group(
+                "group_o".to_owned(),
+                TemplateGroupBuilder::default()
+                    .add_member("A".to_owned(), "template_a".to_owned())
+                    .add_optional_member("b".to_owned(), "template_b".to_owned())
+                    .build(),
+            ).unwrap();
+            builder.require_group::<OptionalGroup>("group_o".to_owned());
+            let result = builder.build();
+            assert!(matches!(result, Err(TerariumBuilderError::MissingGroupMember(group, member))
+                if group == "group_o" && member == "B"));
+
+            let mut builder = make_builder();
+            builder.require_group::<OptionalGroup>("group_x".to_owned());
+            assert!(matches!(builder.build(), Err(TerariumBuilderError::GroupNotFound(_))));
+        }
+
+        #[derive(Debug, PartialEq)]
+        struct OptionalGroup {
+            a: String,
+            b: Option<String>,
+        }
+
+        impl TerariumGroup for OptionalGroup {
+            const MEMBERS: &'static [MemberField] = &[MemberField::new("A"), MemberField::new_optional("B")];
+
+            fn from_rendered(mut rendered: HashMap<String, String>) -> Result<Self, TerariumError> {
+                Ok(Self {
+                    a: rendered.remove("A").ok_or_else(|| TerariumError::MissingMember("A".to_owned()))?,
+                    b: rendered.remove("B"),
+                })
+            }
+        }
+
+        #[test]
+        fn render_template_to_writer() {
+            let instance = make_instance();
+            let mut output = Vec::<u8>::new();
+            instance.render_template_to(&make_context(), "template_a", "cs", None, &mut output).unwrap();
+            assert_eq!(String::from_utf8(output).unwrap(), "template_a cs john");
+
+            let result = instance.render_template_to(&make_context(), "template_b", "cs", None, Vec::new());
+            assert!(matches!(result.unwrap_err(), TerariumError::LanguageNotFound));
+        }
+
+        #[test]
+        fn render_group_to_writers() {
+            let instance = make_instance();
+            let dir = tempfile::TempDir::new().unwrap();
+            instance.render_group_to(&make_context(), "group_a", "cs", Some("en"), |member_key| {
+                std::fs::File::create(dir.path().join(member_key))
+            }).unwrap();
+            assert_eq!(std::fs::read_to_string(dir.path().join("A")).unwrap(), "template_a cs john");
+            assert_eq!(std::fs::read_to_string(dir.path().join("B")).unwrap(), "template_b en doe");
+        }
+
+        #[test]
+        fn render_group_to_writers_errors() {
+            let instance = make_instance();
+            let mut created = 0;
+            let result = instance.render_group_to(&make_context(), "group_a", "cs", None, |_| {
+                created += 1;
+                Ok(Vec::new())
+            });
+            assert!(matches!(result.unwrap_err(), TerariumError::LanguageNotFound));
+            assert_eq!(created, 0);
+
+            let result = instance.render_group_to(&make_context(), "group_a", "en", None, |_| {
+                Err::<Vec<u8>, _>(IoError::new(std::io::ErrorKind::Other, "disk is full"))
+            });
+            assert!(matches!(result.unwrap_err(), TerariumError::WriterFailed(_)));
+        }
+
+        #[test]
+        fn render_group_partial() {
+            let mut builder = make_builder();
+            builder.add_template(
+                "broken".to_owned(),
+                Template::new(vec![Content::new("{{ missing_variable }}".to_owned(), vec!["cs".to_owned()])]).unwrap(),
+            ).unwrap();
+            builder.add_group(
+                "group_b".to_owned(),
+                TemplateGroupBuilder::default()
+                    .add_member("A".to_owned(), "template_a".to_owned())
+                    .add_member("B".to_owned(), "template_b".to_owned())
+                    .add_member("C".to_owned(), "broken".to_owned())
+                    .build(),
+            ).unwrap();
+            let instance = builder.build().unwrap();
+
+            let report = instance.render_group_partial(&make_context(), "group_b", &"cs".into()).unwrap();
+            assert!(!report.is_complete());
+            assert_eq!(report.rendered.iter().collect::<Vec<_>>(), [("A", "template_a cs john")]);
+            assert!(matches!(report.error("B"), Some(TerariumError::LanguageNotFound)));
+            assert!(matches!(report.error("C"), Some(TerariumError::RenderingFailed(_))));
+            assert_eq!(report.failed.iter().map(|(member_key, _)| member_key).collect::<Vec<_>>(), ["B", "C"]);
+
+            let report = instance.render_group_partial(&make_context(), "group_a", &"en".into()).unwrap();
+            assert!(report.is_complete());
+            assert_eq!(report.rendered.len(), 2);
+
+            let result = instance.render_group_partial(&make_context(), "group_x", &"en".into());
+            assert!(matches!(result.unwrap_err(), TerariumError::GroupNotFound));
+        }
+
+        #[test]
+        fn render_group_with_optional_member() {
+            let instance = make_optional_instance();
+            let ctx = make_context();
+
+            let group_result = instance.render_group(&ctx, "group_o", "cs", None).unwrap();
+            assert_eq!(group_result.len(), 1);
+            assert_eq!(group_result["A"], "template_a cs john");
+            assert_eq!(instance.render_group(&ctx, "group_o", "en", None).unwrap()["B"], "template_b en doe");
+            let result = instance.render_group(&ctx, "group_o", "de", None);
+            assert!(matches!(result.unwrap_err(), TerariumError::LanguageNotFound));
+
+            let report = instance.render_group_partial(&ctx, "group_o", &"cs".into()).unwrap();
+            assert!(report.is_complete());
+            assert_eq!(report.rendered.len(), 1);
+
+            let mut created = Vec::new();
+            instance.render_group_to(&ctx, "group_o", "cs", None, |member_key| {
+                created.push(member_key.to_owned());
+                Ok(Vec::new())
+            }).unwrap();
+            assert_eq!(created, vec!["A".to_owned()]);
+        }
+
+        #[test]
+        fn render_group_consistent_with_optional_member() {
+            let instance = make_optional_instance();
+            let group_result = instance.render_group_consistent(&make_context(), "group_o", &"cs".into()).unwrap();
+            assert_eq!(group_result.len(), 1);
+            assert_eq!(instance.group_languages("group_o").unwrap(), vec!["cs", "en"]);
+        }
+
+        #[test]
+        fn render_group_with_context_overlays() {
+            let mut builder = TerariumBuilder::default();
+            let content = "{{ name }} {{ tracking }} {{ footer }}";
+            for key in ["html", "text"] {
+                let template = Template::new(vec![Content::new(content.to_owned(), vec!["en".to_owned()])]).unwrap();
+                builder.add_template(key.to_owned(), template).unwrap();
+            }
+            let mut group_context = Context::new();
+            group_context.insert("footer", "group");
+            group_context.insert("tracking", &false);
+            let mut html_context = Context::new();
+            html_context.insert("tracking", &true);
+            builder.add_group(
+                "email".to_owned(),
+                TemplateGroupBuilder::default()
+                    .add_member("html".to_owned(), "html".to_owned())
+                    .add_member("text".to_owned(), "text".to_owned())
+                    .set_context(group_context)
+                    .set_member_context("html".to_owned(), html_context)
+                    .build(),
+            ).unwrap();
+            let instance = builder.build().unwrap();
+            let mut ctx = Context::new();
+            ctx.insert("name", "john");
+            ctx.insert("footer", "caller");
+
+            let group_result = instance.render_group(&ctx, "email", "en", None).unwrap();
+            assert_eq!(group_result["html"], "john true group");
+            assert_eq!(group_result["text"], "john false group");
+            let group_result = instance.render_group_consistent(&ctx, "email", &"en".into()).unwrap();
+            assert_eq!(group_result["html"], "john true group");
+            let report = instance.render_group_partial(&ctx, "email", &"en".into()).unwrap();
+            assert_eq!(&report.rendered["text"], "john false group");
+            let results = instance.render_group_batch("email", [(ctx.clone(), "en"), (ctx, "en")]).unwrap();
+            for result in results {
+                let group_result = result.unwrap();
+                assert_eq!(&group_result["html"], "john true group");
+                assert_eq!(&group_result["text"], "john false group");
+            }
+        }
+
+        #[test]
+        fn reject_context_of_unknown_member() {
+            let mut builder = make_builder();
+            let result = builder.add_group(
+                "group".to_owned(),
+                TemplateGroupBuilder::default()
+                    .add_member("html".to_owned(), "template_a".to_owned())
+                    .set_member_context("htlm".to_owned(), Context::new())
+                    .build(),
+            );
+            assert!(matches!(
+                result.unwrap_err(),
+                TerariumBuilderError::UnknownContextMember(group, member) if group == "group" && member == "htlm"
+            ));
+        }
+
+        #[test]
+        fn reserved_variables_overwrite_overlays() {
+            let mut builder = make_builder();
+            let mut member_context = Context::new();
+            member_context.insert("__lang", "xx");
+            builder.add_template(
+                "lang".to_owned(),
+                Template::new(vec![Content::new("{{ __lang }}".to_owned(), vec!["en".to_owned()])]).unwrap(),
+            ).unwrap();
+            builder.add_group(
+                "group".to_owned(),
+                TemplateGroupBuilder::default()
+                    .add_member("lang".to_owned(), "lang".to_owned())
+                    .set_member_context("lang".to_owned(), member_context)
+                    .build(),
+            ).unwrap();
+            let instance = builder.build().unwrap();
+            assert_eq!(instance.render_group(&Context::new(), "group", "en", None).unwrap()["lang"], "en");
+        }
+
+        #[test]
+        fn render_all_languages() {
+            let instance = make_instance();
+            let ctx = make_context();
+
+            let result = instance.render_template_all_languages(&ctx, "template_a").unwrap();
+            assert_eq!(result.len(), 2);
+            assert_eq!(result["cs"], "template_a cs john");
+            assert_eq!(result["en"], "template_a en john");
+
+            let result = instance.render_group_all_languages(&ctx, "group_a").unwrap();
+            assert_eq!(result.len(), 2);
+            assert_eq!(&result["en"].rendered["A"], "template_a en john");
+            assert_eq!(&result["en"].rendered["B"], "template_b en doe");
+            assert!(result["en"].failed.is_empty());
+            assert_eq!(&result["cs"].rendered["A"], "template_a cs john");
+            assert!(matches!(result["cs"].error("B"), Some(TerariumError::LanguageNotFound)));
+
+            let result = instance.render_template_all_languages(&ctx, "missing");
+            assert!(matches!(result.unwrap_err(), TerariumError::TemplateNotFound));
+            let result = instance.render_group_all_languages(&ctx, "missing");
+            assert!(matches!(result.unwrap_err(), TerariumError::GroupNotFound));
+        }
+
+        #[test]
+        fn render_all_languages_with_wildcard_member() {
+            let mut builder = TerariumBuilder::default();
+            builder.add_template(
+                "logo".to_owned(),
+                Template::new(vec![Content::new_wildcard("<svg/>".to_owned())]).unwrap(),
+            ).unwrap();
+            builder.add_template(
+                "preheader".to_owned(),
+                Template::new(vec![Content::new("Ahoj".to_owned(), vec!["cs".to_owned()])]).unwrap(),
+            ).unwrap();
+            builder.add_group(
+                "email".to_owned(),
+                TemplateGroupBuilder::default()
+                    .add_member("logo".to_owned(), "logo".to_owned())
+                    .add_optional_member("preheader".to_owned(), "preheader".to_owned())
+                    .build(),
+            ).unwrap();
+            let instance = builder.build().unwrap();
+
+            assert_eq!(instance.group_languages("email").unwrap(), vec!["cs"]);
+            let result = instance.render_group_all_languages(&Context::new(), "email").unwrap();
+            assert_eq!(result.len(), 1);
+            assert_eq!(&result["cs"].rendered["logo"], "<svg/>");
+            assert_eq!(&result["cs"].rendered["preheader"], "Ahoj");
+        }
+
+        #[test]
+        fn available_languages() {
+            let instance = make_instance();
+            assert_eq!(instance.template_languages("template_a").unwrap(), vec!["cs", "en"]);
+            assert_eq!(instance.group_languages("group_a").unwrap(), vec!["en"]);
+            assert!(matches!(instance.template_languages("missing").unwrap_err(), TerariumError::TemplateNotFound));
+            assert!(matches!(instance.group_languages("missing").unwrap_err(), TerariumError::GroupNotFound));
+        }
+
+        #[test]
+        fn negotiate_languages() {
+            let instance = make_instance();
+            let header = "de-DE, cs-CZ;q=0.9, EN-us;q=0.8";
+
+            let preference = instance.negotiate_template("template_a", header).unwrap();
+            assert_eq!(preference.languages(), ["cs", "en"]);
+            let rendered = instance.render_template_with_preference(&make_context(), "template_a", &preference).unwrap();
+            assert_eq!(rendered, "template_a cs john");
+
+            assert_eq!(instance.negotiate_group("group_a", header).unwrap().primary(), Some("en"));
+            assert!(instance.negotiate_group("group_a", "de").unwrap().is_empty());
+        }
+
+        fn make_wildcard_instance() -> Terarium {
+            make_wildcard_builder().build().unwrap()
+        }
+
+        fn make_wildcard_builder() -> TerariumBuilder {
+            let mut builder = make_builder();
+            builder.add_template(
+                "template_c".to_owned(),
+                Template::new(vec![
+                    Content::new("template_c cs".to_owned(), vec!["cs".to_owned()]),
+                    Content::new_wildcard("template_c any".to_owned()),
+                ]).unwrap(),
+            ).unwrap();
+            builder.add_template(
+                "template_d".to_owned(),
+                Template::new(vec![Content::new_wildcard("template_d any".to_owned())]).unwrap(),
+            ).unwrap();
+            builder.add_group(
+                "group_b".to_owned(),
+                TemplateGroupBuilder::default()
+                    .add_member("A".to_owned(), "template_a".to_owned())
+                    .add_member("C".to_owned(), "template_c".to_owned())
+                    .add_member("D".to_owned(), "template_d".to_owned())
+                    .build(),
+            ).unwrap();
+            builder
+        }
+
+        fn make_optional_instance() -> Terarium {
+            let mut builder = make_builder();
+            builder.add_group(
+                "group_o".to_owned(),
+                TemplateGroupBuilder::default()
+                    .add_member("A".to_owned(), "template_a".to_owned())
+                    .add_optional_member("B".to_owned(), "template_b".to_owned())
+                    .build(),
+            ).unwrap();
+            builder.build().unwrap()
+        }
+
+        fn make_tagged_instance() -> Terarium {
+            let mut builder = TerariumBuilder::default();
+            builder.set_language_tags(true);
+            builder.add_template(
+                "template_a".to_owned(),
+                Template::new(vec![
+                    Content::new("template_a pt {{name}}".to_owned(), vec!["pt".to_owned()]),
+                    Content::new("template_a de {{name}}".to_owned(), vec!["DE".to_owned()]),
+                    Content::new("template_a de-AT {{name}}".to_owned(), vec!["de_at".to_owned()]),
+                ]).unwrap(),
+            ).unwrap();
+            builder.build().unwrap()
+        }
+
+        fn make_instance() -> Terarium {
+            make_builder().build().unwrap()
+        }
+
+        fn make_builder() -> TerariumBuilder {
+            let mut builder = TerariumBuilder::default();
+
+            let mut tpl_a = Template::default();
+            tpl_a.add_content(Content::new("template_a cs {{name}}".to_owned(), vec!["cs".to_owned()])).unwrap();
+            tpl_a.add_content(Content::new("template_a en {{name}}".to_owned(), vec!["en".to_owned()])).unwrap();
 
             let mut tpl_b = Template::default();
             tpl_b.add_content(Content::new("template_b en {{surname}}".to_owned(), vec!["en".to_owned()])).unwrap();
@@ -405,7 +2284,7 @@ mod tests {
                     .add_member("B".to_owned(), "template_b".to_owned())
                     .build(),
             ).unwrap();
-            builder.build().unwrap()
+            builder
         }
 
         fn make_context() -> Context {
@@ -0,0 +1,39 @@
+use std::collections::HashMap;
+
+use crate::TerariumError;
+
+/// Struct with fields mapped to members of the template group.
+///
+/// Usually implemented by the `#[derive(TerariumGroup)]` macro of the `terarium-macros` crate. Register the type by
+/// the `TerariumBuilder::require_group()` method to check members of the group when the instance is built and render it
+/// by the `Terarium::render_group_as()` method.
+pub trait TerariumGroup: Sized {
+    /// Members mapped to fields of the struct.
+    const MEMBERS: &'static [MemberField];
+
+    /// Create the struct from rendered contents by member keys.
+    fn from_rendered(rendered: HashMap<String, String>) -> Result<Self, TerariumError>;
+}
+
+
+/// Group member mapped to field of the `TerariumGroup` struct.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MemberField {
+    /// Key of the group member.
+    pub member_key: &'static str,
+    /// The field is optional (e.g. `Option<String>`), so the member may be left out of the render result.
+    pub optional: bool,
+}
+
+
+impl MemberField {
+    /// Create new mandatory field.
+    pub const fn new(member_key: &'static str) -> Self {
+        Self { member_key, optional: false }
+    }
+
+    /// Create new optional field.
+    pub const fn new_optional(member_key: &'static str) -> Self {
+        Self { member_key, optional: true }
+    }
+}
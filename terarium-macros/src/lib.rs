@@ -0,0 +1,274 @@
+//! # Terarium macros
+//!
+//! Macros for the [Terarium](https://crates.io/crates/terarium) library.
+//!
+//! ## Embedding templates
+//!
+//! The `embed_directory!` and `embed_manifest!` macros embed the template catalog into the binary at compile time, so
+//! no template files are needed at runtime. Both macros accept path relative to the crate root (the directory with
+//! `Cargo.toml`) and are expanded into the `TerariumBuilder` expression.
+//!
+//! The catalog is loaded and built during compilation, so invalid file names, conflicting contents, missing group
+//! templates and Tera syntax errors are reported by `cargo build`. The manifest is parsed during compilation as well,
+//! so no format feature of the `terarium` crate is needed at runtime.
+//!
+//! ```rust
+//! use terarium::tera::Context;
+//! use terarium_macros::{embed_directory, embed_manifest};
+//!
+//! let mut context = Context::new();
+//! context.insert("name", "john");
+//!
+//! let terarium = embed_directory!("tests/templates").build().unwrap();
+//! let rendered = terarium.render_template(&context, "emails/welcome/subject", "en", None).unwrap();
+//! assert_eq!(rendered, "Welcome john");
+//!
+//! let terarium = embed_manifest!("tests/catalog.toml").build().unwrap();
+//! let rendered = terarium.render_group(&context, "email", "cs", None).unwrap();
+//! assert_eq!(rendered["subject"], "Ahoj john");
+//! ```
+//!
+//! Note: the embedded files are tracked by the compiler, but new files added into the directory are not. Touch the
+//! source file with the macro call (or run `cargo clean`) when a new template file is added.
+//!
+//! ## Typed groups
+//!
+//! The `#[derive(TerariumGroup)]` macro implements the `TerariumGroup` trait for struct with named fields. Each field
+//! is mapped to the group member with the same key (or the key set by the `#[terarium(member = "...")]` attribute).
+//! Fields of the `Option` type are optional members.
+//!
+//! ```rust,ignore
+//! use terarium_macros::TerariumGroup;
+//!
+//! #[derive(TerariumGroup)]
+//! struct Email {
+//!     subject: String,
+//!     text: String,
+//!     html: Option<String>,
+//! }
+//!
+//! builder.require_group::<Email>("welcome".to_owned());
+//! let terarium = builder.build().unwrap();
+//! let email: Email = terarium.render_group_as(&context, "welcome", &"en".into()).unwrap();
+//! ```
+
+use std::collections::HashSet;
+use std::error::Error;
+use std::path::PathBuf;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, LitStr, Type, parse_macro_input};
+use terarium::{Content, DirectoryLoader, Manifest, TemplateSource};
+
+/// Embed templates from the directory. The directory is loaded by the `DirectoryLoader` rules.
+///
+/// Expanded into the `TerariumBuilder` expression.
+#[proc_macro]
+pub fn embed_directory(input: TokenStream) -> TokenStream {
+    let path = parse_macro_input!(input as LitStr);
+    expand_directory(&path).unwrap_or_else(|err| err.to_compile_error()).into()
+}
+
+
+/// Embed templates and groups defined by the manifest file. The format is selected by the file extension (`toml`,
+/// `json`, `yaml` or `yml`) and the corresponding feature of the `terarium` crate has to be enabled.
+///
+/// Expanded into the `TerariumBuilder` expression.
+#[proc_macro]
+pub fn embed_manifest(input: TokenStream) -> TokenStream {
+    let path = parse_macro_input!(input as LitStr);
+    expand_manifest(&path).unwrap_or_else(|err| err.to_compile_error()).into()
+}
+
+
+/// Derive the `TerariumGroup` trait for struct with named fields.
+///
+/// Fields are mapped to group members by their names or by the `#[terarium(member = "...")]` attribute. Fields of the
+/// `Option` type are optional members, other fields have to be `String`. Each member can be mapped by one field only.
+#[proc_macro_derive(TerariumGroup, attributes(terarium))]
+pub fn derive_terarium_group(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_terarium_group(&input).unwrap_or_else(|err| err.to_compile_error()).into()
+}
+
+
+/// Generate the `TerariumGroup` implementation.
+fn expand_terarium_group(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(syn::Error::new_spanned(input, "TerariumGroup requires struct with named fields")),
+        },
+        _ => return Err(syn::Error::new_spanned(input, "TerariumGroup can be derived only for structs")),
+    };
+
+    let mut member_keys = HashSet::new();
+    let mut members = Vec::new();
+    let mut initializers = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().expect("named field has ident");
+        let member_key = member_key(field)?.unwrap_or_else(|| ident.to_string());
+        if !member_keys.insert(member_key.clone()) {
+            return Err(syn::Error::new_spanned(field, format!("member {} is mapped by multiple fields", member_key)));
+        }
+        if is_option(&field.ty) {
+            members.push(quote! { ::terarium::MemberField::new_optional(#member_key) });
+            initializers.push(quote! { #ident: rendered.remove(#member_key) });
+        } else {
+            members.push(quote! { ::terarium::MemberField::new(#member_key) });
+            initializers.push(quote! {
+                #ident: rendered
+                    .remove(#member_key)
+                    .ok_or_else(|| ::terarium::TerariumError::MissingMember(#member_key.to_owned()))?
+            });
+        }
+    }
+
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::terarium::TerariumGroup for #name #type_generics #where_clause {
+            const MEMBERS: &'static [::terarium::MemberField] = &[#(#members),*];
+
+            fn from_rendered(
+                mut rendered: ::std::collections::HashMap<::std::string::String, ::std::string::String>,
+            ) -> ::std::result::Result<Self, ::terarium::TerariumError> {
+                ::std::result::Result::Ok(Self { #(#initializers),* })
+            }
+        }
+    })
+}
+
+
+/// Get member key set by the `#[terarium(member = "...")]` attribute of the field.
+fn member_key(field: &syn::Field) -> syn::Result<Option<String>> {
+    let mut member_key = None;
+    for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("terarium")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("member") {
+                member_key = Some(meta.value()?.parse::<LitStr>()?.value());
+                Ok(())
+            } else {
+                Err(meta.error("unsupported terarium attribute"))
+            }
+        })?;
+    }
+    Ok(member_key)
+}
+
+
+/// Return `true` if the type is the `Option`.
+fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path.qself.is_none() && path.path.segments.last().is_some_and(|s| s.ident == "Option"),
+        _ => false,
+    }
+}
+
+
+/// Validate the directory and generate code embedding its files.
+fn expand_directory(path: &LitStr) -> syn::Result<TokenStream2> {
+    let root = resolve_path(path)?;
+    let loader = DirectoryLoader::new(root.clone());
+    let files = loader.files().map_err(|err| compile_error(path, err))?;
+    let builder = loader.load().map_err(|err| compile_error(path, err))?;
+    builder.build().map_err(|err| compile_error(path, err))?;
+
+    let entries = files.iter().map(|file| {
+        let full_path = root.join(file).to_string_lossy().into_owned();
+        quote! { (#file, include_str!(#full_path)) }
+    });
+    Ok(quote! {
+        ::terarium::DirectoryLoader::load_embedded(&[#(#entries),*])
+            .expect("embedded templates are validated at compile time")
+    })
+}
+
+
+/// Validate the manifest and generate code embedding it.
+///
+/// The manifest is parsed at compile time and expanded into constructors of templates and groups, so the generated
+/// code does not depend on format features of the `terarium` crate used by the caller.
+fn expand_manifest(path: &LitStr) -> syn::Result<TokenStream2> {
+    let full_path = resolve_path(path)?;
+    let manifest = Manifest::from_file(&full_path).map_err(|err| compile_error(path, err))?;
+    let templates = manifest.templates().map_err(|err| compile_error(path, err))?;
+    let groups = manifest.groups().map_err(|err| compile_error(path, err))?;
+    let builder = manifest.into_builder().map_err(|err| compile_error(path, err))?;
+    builder.build().map_err(|err| compile_error(path, err))?;
+
+    let templates = templates.iter().map(|(key, contents)| {
+        let contents = contents.iter().map(expand_content);
+        quote! {
+            builder
+                .add_template(
+                    #key.to_owned(),
+                    ::terarium::Template::new(::std::vec![#(#contents),*])
+                        .expect("embedded manifest is validated at compile time"),
+                )
+                .expect("embedded manifest is validated at compile time");
+        }
+    });
+    let groups = groups.iter().map(|(key, group)| {
+        let members = group.iter().map(|(member_key, member)| {
+            let template_key = &member.template_key;
+            let constructor = match member.optional {
+                true => quote! { ::terarium::GroupMember::new_optional },
+                false => quote! { ::terarium::GroupMember::new },
+            };
+            quote! { group.add_member(#member_key.to_owned(), #constructor(#template_key.to_owned())); }
+        });
+        quote! {
+            let mut group = ::terarium::TemplateGroup::default();
+            #(#members)*
+            builder.add_group(#key.to_owned(), group).expect("embedded manifest is validated at compile time");
+        }
+    });
+    let full_path = full_path.to_string_lossy().into_owned();
+    Ok(quote! {
+        {
+            // Recompile when the manifest changes.
+            const _: &str = include_str!(#full_path);
+            let mut builder = ::terarium::TerariumBuilder::default();
+            #(#templates)*
+            #(#groups)*
+            builder
+        }
+    })
+}
+
+
+/// Generate constructor of the template content.
+fn expand_content(content: &Content) -> TokenStream2 {
+    let text = &content.content;
+    let languages = &content.languages;
+    let languages = quote! { ::std::vec![#(#languages.to_owned()),*] };
+    match &content.name {
+        Some(name) => quote! { ::terarium::Content::new_named(#text.to_owned(), #languages, #name.to_owned()) },
+        None => quote! { ::terarium::Content::new(#text.to_owned(), #languages) },
+    }
+}
+
+
+/// Resolve path relative to the root of the crate being compiled.
+fn resolve_path(path: &LitStr) -> syn::Result<PathBuf> {
+    let root = std::env::var("CARGO_MANIFEST_DIR")
+        .map_err(|_| syn::Error::new(path.span(), "CARGO_MANIFEST_DIR is not set"))?;
+    Ok(PathBuf::from(root).join(path.value()))
+}
+
+
+/// Convert error into compile error pointing to the path literal.
+/// The message contains the error and all its sources.
+fn compile_error(path: &LitStr, err: impl Into<Box<dyn Error + Send + Sync>>) -> syn::Error {
+    let err = err.into();
+    let mut message = err.to_string();
+    let mut source = err.source();
+    while let Some(err) = source {
+        message.push_str(&format!(": {}", err));
+        source = err.source();
+    }
+    syn::Error::new(path.span(), message)
+}
@@ -0,0 +1,27 @@
+use terarium::tera::Context;
+use terarium_macros::{embed_directory, embed_manifest};
+
+#[test]
+fn embed_templates_directory() {
+    let terarium = embed_directory!("tests/templates").build().unwrap();
+    let group = terarium.render_group(&make_context(), "emails/welcome", "en", None).unwrap();
+    assert_eq!(group["subject"], "Welcome john");
+    assert_eq!(group["html"], "<p>Welcome john</p>");
+    assert_eq!(
+        terarium.render_template(&make_context(), "emails/welcome/subject", "cs", None).unwrap(),
+        "Vitejte john",
+    );
+}
+
+#[test]
+fn embed_manifest_file() {
+    let terarium = embed_manifest!("tests/catalog.toml").build().unwrap();
+    let group = terarium.render_group(&make_context(), "email", "cs", None).unwrap();
+    assert_eq!(group["subject"], "Ahoj john");
+}
+
+fn make_context() -> Context {
+    let mut ctx = Context::new();
+    ctx.insert("name", "john");
+    ctx
+}
@@ -0,0 +1,80 @@
+use terarium::{Content, LanguagePreference, MemberField, Template, TemplateGroupBuilder, TerariumBuilder};
+use terarium::{TerariumBuilderError, TerariumGroup};
+use terarium::tera::Context;
+use terarium_macros::TerariumGroup;
+
+#[derive(Debug, PartialEq, TerariumGroup)]
+struct Email {
+    subject: String,
+    #[terarium(member = "body")]
+    text: String,
+    html: Option<String>,
+}
+
+#[test]
+fn derive_group_members() {
+    assert_eq!(
+        Email::MEMBERS,
+        [MemberField::new("subject"), MemberField::new("body"), MemberField::new_optional("html")],
+    );
+}
+
+#[test]
+fn render_group_as_struct() {
+    let mut builder = make_builder();
+    builder.require_group::<Email>("email".to_owned());
+    let terarium = builder.build().unwrap();
+
+    let email: Email = terarium.render_group_as(&make_context(), "email", &LanguagePreference::new("en")).unwrap();
+    assert_eq!(email, Email {
+        subject: "Hello john".to_owned(),
+        text: "Text john".to_owned(),
+        html: Some("<p>Html john</p>".to_owned()),
+    });
+    let email: Email = terarium.render_group_as(&make_context(), "email", &LanguagePreference::new("cs")).unwrap();
+    assert_eq!(email.html, None);
+}
+
+#[test]
+fn require_group_members() {
+    let mut builder = make_builder();
+    builder.add_group(
+        "subject_only".to_owned(),
+        TemplateGroupBuilder::default().add_member("subject".to_owned(), "subject".to_owned()).build(),
+    ).unwrap();
+    builder.require_group::<Email>("subject_only".to_owned());
+    assert!(matches!(builder.build(), Err(TerariumBuilderError::MissingGroupMember(_, member)) if member == "body"));
+}
+
+fn make_builder() -> TerariumBuilder {
+    let mut builder = TerariumBuilder::default();
+    let contents = [("subject", "Hello {{ name }}", "Ahoj {{ name }}"), ("text", "Text {{ name }}", "Text {{ name }}")];
+    for (key, en, cs) in contents {
+        builder.add_template(
+            key.to_owned(),
+            Template::new(vec![
+                Content::new(en.to_owned(), vec!["en".to_owned()]),
+                Content::new(cs.to_owned(), vec!["cs".to_owned()]),
+            ]).unwrap(),
+        ).unwrap();
+    }
+    builder.add_template(
+        "html".to_owned(),
+        Template::new(vec![Content::new("<p>Html {{ name }}</p>".to_owned(), vec!["en".to_owned()])]).unwrap(),
+    ).unwrap();
+    builder.add_group(
+        "email".to_owned(),
+        TemplateGroupBuilder::default()
+            .add_member("subject".to_owned(), "subject".to_owned())
+            .add_member("body".to_owned(), "text".to_owned())
+            .add_optional_member("html".to_owned(), "html".to_owned())
+            .build(),
+    ).unwrap();
+    builder
+}
+
+fn make_context() -> Context {
+    let mut ctx = Context::new();
+    ctx.insert("name", "john");
+    ctx
+}
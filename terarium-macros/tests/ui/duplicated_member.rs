@@ -0,0 +1,10 @@
+use terarium_macros::TerariumGroup;
+
+#[derive(TerariumGroup)]
+struct Email {
+    #[terarium(member = "body")]
+    text: String,
+    body: Option<String>,
+}
+
+fn main() {}
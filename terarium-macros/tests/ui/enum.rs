@@ -0,0 +1,8 @@
+use terarium_macros::TerariumGroup;
+
+#[derive(TerariumGroup)]
+enum Email {
+    Subject(String),
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use terarium_macros::TerariumGroup;
+
+#[derive(TerariumGroup)]
+struct Email(String, String);
+
+fn main() {}
@@ -0,0 +1,9 @@
+use terarium_macros::TerariumGroup;
+
+#[derive(TerariumGroup)]
+struct Email {
+    #[terarium(template = "subject")]
+    subject: String,
+}
+
+fn main() {}